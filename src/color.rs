@@ -0,0 +1,259 @@
+use std::env;
+use std::io::{self, IsTerminal};
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+
+use clap::ValueEnum;
+use comfy_table::{Color, Table};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum ColorMode {
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+/// Number of colors the terminal can show. Themes are defined in truecolor and
+/// quantised to the nearest palette entry on lesser terminals.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ColorDepth {
+    Truecolor,
+    #[value(name = "256")]
+    Ansi256,
+    #[value(name = "16")]
+    Ansi16,
+}
+
+static COLOR_ENABLED: AtomicBool = AtomicBool::new(true);
+static COLOR_DEPTH: AtomicU8 = AtomicU8::new(ColorDepth::Truecolor as u8);
+
+/// Decides once at startup whether escape codes are written. An explicit
+/// `--color always|never` wins; in `auto` mode `NO_COLOR` disables colors,
+/// `CLICOLOR_FORCE` enables them and otherwise stdout has to be a terminal.
+/// Without `--color-depth` the depth is guessed from `COLORTERM` and `TERM`.
+pub fn init(mode: ColorMode, depth: Option<ColorDepth>) {
+    let enabled = match mode {
+        ColorMode::Always => true,
+        ColorMode::Never => false,
+        ColorMode::Auto => detect(),
+    };
+    COLOR_ENABLED.store(enabled, Ordering::Relaxed);
+
+    let depth = depth.unwrap_or_else(|| {
+        detect_depth(
+            env::var("COLORTERM").ok().as_deref(),
+            env::var("TERM").ok().as_deref(),
+        )
+    });
+    COLOR_DEPTH.store(depth as u8, Ordering::Relaxed);
+}
+
+fn detect() -> bool {
+    if env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()) {
+        return false;
+    }
+    if env::var_os("CLICOLOR_FORCE").is_some_and(|v| !v.is_empty() && v != "0") {
+        return true;
+    }
+    io::stdout().is_terminal()
+}
+
+fn detect_depth(colorterm: Option<&str>, term: Option<&str>) -> ColorDepth {
+    if matches!(colorterm, Some("truecolor") | Some("24bit")) {
+        return ColorDepth::Truecolor;
+    }
+
+    match term.unwrap_or("") {
+        "" | "dumb" | "linux" | "vt100" | "vt220" | "ansi" | "cons25" => ColorDepth::Ansi16,
+        term if term.ends_with("-direct") || term.contains("truecolor") => ColorDepth::Truecolor,
+        term if term.contains("256color") => ColorDepth::Ansi256,
+        "xterm-kitty" | "alacritty" | "wezterm" | "foot" => ColorDepth::Truecolor,
+        _ => ColorDepth::Ansi16,
+    }
+}
+
+pub fn enabled() -> bool {
+    COLOR_ENABLED.load(Ordering::Relaxed)
+}
+
+pub fn depth() -> ColorDepth {
+    match COLOR_DEPTH.load(Ordering::Relaxed) {
+        d if d == ColorDepth::Ansi256 as u8 => ColorDepth::Ansi256,
+        d if d == ColorDepth::Ansi16 as u8 => ColorDepth::Ansi16,
+        _ => ColorDepth::Truecolor,
+    }
+}
+
+/// Escape sequence switching the foreground to `color`, or nothing when
+/// colors are disabled.
+pub fn fg(color: (u8, u8, u8)) -> String {
+    if !enabled() {
+        return String::new();
+    }
+    match depth() {
+        ColorDepth::Truecolor => format!("\x1b[38;2;{};{};{}m", color.0, color.1, color.2),
+        ColorDepth::Ansi256 => format!("\x1b[38;5;{}m", to_ansi256(color)),
+        ColorDepth::Ansi16 => match to_ansi16(color) {
+            index @ 0..=7 => format!("\x1b[{}m", 30 + index),
+            index => format!("\x1b[{}m", 90 + index - 8),
+        },
+    }
+}
+
+/// Table cell color for a theme slot at the current color depth.
+pub fn cell_color(color: (u8, u8, u8)) -> Color {
+    match depth() {
+        ColorDepth::Truecolor => Color::Rgb {
+            r: color.0,
+            g: color.1,
+            b: color.2,
+        },
+        ColorDepth::Ansi256 => Color::AnsiValue(to_ansi256(color)),
+        // Named colors, since the 16-color consoles don't understand `38;5;n`
+        ColorDepth::Ansi16 => match to_ansi16(color) {
+            0 => Color::Black,
+            1 => Color::DarkRed,
+            2 => Color::DarkGreen,
+            3 => Color::DarkYellow,
+            4 => Color::DarkBlue,
+            5 => Color::DarkMagenta,
+            6 => Color::DarkCyan,
+            7 => Color::Grey,
+            8 => Color::DarkGrey,
+            9 => Color::Red,
+            10 => Color::Green,
+            11 => Color::Yellow,
+            12 => Color::Blue,
+            13 => Color::Magenta,
+            14 => Color::Cyan,
+            _ => Color::White,
+        },
+    }
+}
+
+// The standard xterm palette for the first 16 colors
+const ANSI16_PALETTE: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    // Weighted euclidean distance, close enough to perceptual for picking
+    // between palette entries
+    let dr = a.0 as i32 - b.0 as i32;
+    let dg = a.1 as i32 - b.1 as i32;
+    let db = a.2 as i32 - b.2 as i32;
+    (2 * dr * dr + 4 * dg * dg + 3 * db * db) as u32
+}
+
+/// Nearest entry of the xterm-256 palette, looking at both the 6x6x6 color
+/// cube and the 24-step grayscale ramp.
+pub fn to_ansi256(color: (u8, u8, u8)) -> u8 {
+    let nearest_level = |c: u8| {
+        (0..6)
+            .min_by_key(|&i| (CUBE_LEVELS[i] as i32 - c as i32).abs())
+            .unwrap()
+    };
+    let (r, g, b) = (
+        nearest_level(color.0),
+        nearest_level(color.1),
+        nearest_level(color.2),
+    );
+    let cube_index = 16 + 36 * r + 6 * g + b;
+    let cube_color = (CUBE_LEVELS[r], CUBE_LEVELS[g], CUBE_LEVELS[b]);
+
+    let average = (color.0 as u32 + color.1 as u32 + color.2 as u32) / 3;
+    let gray_step = (average.saturating_sub(8) / 10).min(23) as u8;
+    let gray_level = 8 + 10 * gray_step;
+    let gray_color = (gray_level, gray_level, gray_level);
+
+    if distance(color, gray_color) < distance(color, cube_color) {
+        232 + gray_step
+    } else {
+        cube_index as u8
+    }
+}
+
+/// Nearest of the 16 basic ANSI colors. Clearly colored slots never map to
+/// black, white or gray, otherwise most pastel themes would turn colorless.
+pub fn to_ansi16(color: (u8, u8, u8)) -> u8 {
+    const GRAYS: [u8; 4] = [0, 7, 8, 15];
+    let max = color.0.max(color.1).max(color.2);
+    let min = color.0.min(color.1).min(color.2);
+    let colorful = max - min >= 48;
+
+    (0..16)
+        .filter(|i| !(colorful && GRAYS.contains(i)))
+        .min_by_key(|&i| distance(color, ANSI16_PALETTE[i as usize]))
+        .unwrap()
+}
+
+pub fn reset() -> &'static str {
+    if enabled() { "\x1b[0m" } else { "" }
+}
+
+/// A table whose cell styling follows the color mode rather than comfy-table's
+/// own terminal check, so `--color always` also colors piped output.
+pub fn new_table() -> Table {
+    let mut table = Table::new();
+    if enabled() {
+        table.enforce_styling();
+    } else {
+        // Keep wrapping to the terminal width while dropping the styling
+        let width = table.width();
+        table.force_no_tty();
+        if let Some(width) = width {
+            table.set_width(width);
+        }
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ColorDepth, detect_depth, to_ansi16, to_ansi256};
+
+    #[test]
+    fn test_detect_depth_from_environment() {
+        assert_eq!(detect_depth(Some("truecolor"), Some("tmux-256color")), ColorDepth::Truecolor);
+        assert_eq!(detect_depth(None, Some("tmux-256color")), ColorDepth::Ansi256);
+        assert_eq!(detect_depth(None, Some("linux")), ColorDepth::Ansi16);
+        assert_eq!(detect_depth(None, Some("xterm-kitty")), ColorDepth::Truecolor);
+    }
+
+    #[test]
+    fn test_ansi256_exact_palette_entries() {
+        assert_eq!(to_ansi256((0, 0, 0)), 16);
+        assert_eq!(to_ansi256((255, 255, 255)), 231);
+        assert_eq!(to_ansi256((255, 0, 0)), 196);
+        assert_eq!(to_ansi256((135, 175, 215)), 110);
+    }
+
+    #[test]
+    fn test_ansi256_prefers_grayscale_ramp_for_grays() {
+        assert_eq!(to_ansi256((128, 128, 128)), 244);
+    }
+
+    #[test]
+    fn test_ansi16_nearest_color() {
+        assert_eq!(to_ansi16((250, 10, 10)), 9);
+        assert_eq!(to_ansi16((20, 20, 20)), 0);
+        assert_eq!(to_ansi16((137, 180, 250)), 12);
+    }
+}
@@ -0,0 +1,253 @@
+#[cfg(unix)]
+use std::collections::HashMap;
+#[cfg(unix)]
+use std::ffi::CStr;
+use std::fs;
+#[cfg(unix)]
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use clap::ValueEnum;
+#[cfg(unix)]
+use lazy_static::lazy_static;
+
+use crate::themes::Theme;
+
+/// Columns of the directory table, chosen with `--columns` or `columns` in
+/// config.toml.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Column {
+    /// Row number
+    Index,
+    Name,
+    Type,
+    Size,
+    #[value(alias = "modified")]
+    Mtime,
+    #[value(alias = "accessed")]
+    Atime,
+    /// Last status change
+    #[value(alias = "changed")]
+    Ctime,
+    /// Creation time, where the filesystem records it
+    #[value(alias = "created")]
+    Btime,
+    #[value(alias = "perms")]
+    Permissions,
+    /// Permission bits as octal, e.g. 0644
+    Octal,
+    #[value(alias = "user")]
+    Owner,
+    Group,
+    Inode,
+    /// Number of hard links
+    Links,
+}
+
+pub const DEFAULT_COLUMNS: [Column; 6] = [
+    Column::Index,
+    Column::Name,
+    Column::Type,
+    Column::Size,
+    Column::Mtime,
+    Column::Permissions,
+];
+
+impl Column {
+    pub fn header(&self) -> &'static str {
+        match self {
+            Column::Index => "#",
+            Column::Name => "Name",
+            Column::Type => "Type",
+            Column::Size => "Size",
+            Column::Mtime => "Modified",
+            Column::Atime => "Accessed",
+            Column::Ctime => "Changed",
+            Column::Btime => "Created",
+            Column::Permissions => "Permissions",
+            Column::Octal => "Octal",
+            Column::Owner => "Owner",
+            Column::Group => "Group",
+            Column::Inode => "Inode",
+            Column::Links => "Links",
+        }
+    }
+
+    /// Color of the column, `None` for the ones colored by entry type.
+    pub fn color(&self, theme: &Theme) -> Option<(u8, u8, u8)> {
+        Some(match self {
+            Column::Index => theme.row_number,
+            Column::Mtime => theme.modified,
+            Column::Permissions => theme.permissions,
+            Column::Atime => theme.atime_color(),
+            Column::Ctime => theme.ctime_color(),
+            Column::Btime => theme.btime_color(),
+            Column::Octal => theme.octal_color(),
+            Column::Owner => theme.owner_color(),
+            Column::Group => theme.group_color(),
+            Column::Inode => theme.inode_color(),
+            Column::Links => theme.links_color(),
+            Column::Name | Column::Type | Column::Size => return None,
+        })
+    }
+}
+
+/// Parses the `columns` list from config.toml, naming the first unknown
+/// column on error.
+pub fn parse_columns(names: &[String]) -> Result<Vec<Column>, String> {
+    names
+        .iter()
+        .map(|name| {
+            Column::from_str(name.trim(), true).map_err(|_| format!("unknown column \"{name}\""))
+        })
+        .collect()
+}
+
+/// Metadata behind the optional columns. Fields are `None` where the
+/// platform or filesystem does not provide them.
+#[derive(Debug, Default)]
+pub struct Details {
+    pub owner: Option<String>,
+    pub group: Option<String>,
+    pub inode: Option<u64>,
+    pub links: Option<u64>,
+    pub accessed: Option<SystemTime>,
+    pub changed: Option<SystemTime>,
+    pub created: Option<SystemTime>,
+}
+
+impl Details {
+    #[cfg(unix)]
+    pub fn read(metadata: &fs::Metadata) -> Details {
+        use std::os::unix::fs::MetadataExt;
+
+        let changed = SystemTime::UNIX_EPOCH.checked_add(std::time::Duration::new(
+            metadata.ctime().max(0) as u64,
+            metadata.ctime_nsec().clamp(0, 999_999_999) as u32,
+        ));
+        Details {
+            owner: Some(user_name(metadata.uid())),
+            group: Some(group_name(metadata.gid())),
+            inode: Some(metadata.ino()),
+            links: Some(metadata.nlink()),
+            accessed: metadata.accessed().ok(),
+            changed,
+            created: metadata.created().ok(),
+        }
+    }
+
+    #[cfg(not(unix))]
+    pub fn read(metadata: &fs::Metadata) -> Details {
+        Details {
+            accessed: metadata.accessed().ok(),
+            created: metadata.created().ok(),
+            ..Details::default()
+        }
+    }
+}
+
+#[cfg(unix)]
+lazy_static! {
+    // Resolved names by uid/gid, most listings only have a handful
+    static ref USER_NAMES: Mutex<HashMap<u32, String>> = Mutex::new(HashMap::new());
+    static ref GROUP_NAMES: Mutex<HashMap<u32, String>> = Mutex::new(HashMap::new());
+}
+
+/// Name of the user with `uid`, or the number when it has no name.
+#[cfg(unix)]
+pub fn user_name(uid: u32) -> String {
+    cached(&USER_NAMES, uid, lookup_user)
+}
+
+/// Name of the group with `gid`, or the number when it has no name.
+#[cfg(unix)]
+pub fn group_name(gid: u32) -> String {
+    cached(&GROUP_NAMES, gid, lookup_group)
+}
+
+#[cfg(unix)]
+fn cached(
+    cache: &Mutex<HashMap<u32, String>>,
+    id: u32,
+    lookup: fn(u32) -> Option<String>,
+) -> String {
+    let mut cache = cache.lock().unwrap_or_else(|e| e.into_inner());
+    cache
+        .entry(id)
+        .or_insert_with(|| lookup(id).unwrap_or_else(|| id.to_string()))
+        .clone()
+}
+
+// Upper bound for the string buffer of getpwuid_r/getgrgid_r, which report
+// ERANGE while it is too small
+#[cfg(unix)]
+const MAX_LOOKUP_BUFFER: usize = 1 << 20;
+
+#[cfg(unix)]
+fn lookup_user(uid: u32) -> Option<String> {
+    let mut buf: Vec<libc::c_char> = vec![0; 1024];
+    loop {
+        // SAFETY: passwd is a plain C struct, all zeroes is a valid value
+        let mut pwd: libc::passwd = unsafe { std::mem::zeroed() };
+        let mut result = std::ptr::null_mut();
+        // SAFETY: all pointers are valid for the duration of the call
+        let code =
+            unsafe { libc::getpwuid_r(uid, &mut pwd, buf.as_mut_ptr(), buf.len(), &mut result) };
+        if code == libc::ERANGE && buf.len() < MAX_LOOKUP_BUFFER {
+            buf.resize(buf.len() * 2, 0);
+            continue;
+        }
+        if code != 0 || result.is_null() {
+            return None;
+        }
+        // SAFETY: pw_name points into `buf`, which is still alive
+        return Some(unsafe { CStr::from_ptr(pwd.pw_name) }.to_string_lossy().into_owned());
+    }
+}
+
+#[cfg(unix)]
+fn lookup_group(gid: u32) -> Option<String> {
+    let mut buf: Vec<libc::c_char> = vec![0; 1024];
+    loop {
+        // SAFETY: group is a plain C struct, all zeroes is a valid value
+        let mut grp: libc::group = unsafe { std::mem::zeroed() };
+        let mut result = std::ptr::null_mut();
+        // SAFETY: all pointers are valid for the duration of the call
+        let code =
+            unsafe { libc::getgrgid_r(gid, &mut grp, buf.as_mut_ptr(), buf.len(), &mut result) };
+        if code == libc::ERANGE && buf.len() < MAX_LOOKUP_BUFFER {
+            buf.resize(buf.len() * 2, 0);
+            continue;
+        }
+        if code != 0 || result.is_null() {
+            return None;
+        }
+        // SAFETY: gr_name points into `buf`, which is still alive
+        return Some(unsafe { CStr::from_ptr(grp.gr_name) }.to_string_lossy().into_owned());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Column, parse_columns};
+
+    #[test]
+    fn test_parse_columns_accepts_aliases() {
+        let names = ["name", "Modified", "perms", "owner"].map(String::from);
+        assert_eq!(
+            parse_columns(&names).unwrap(),
+            [Column::Name, Column::Mtime, Column::Permissions, Column::Owner]
+        );
+
+        let error = parse_columns(&["name".to_string(), "colour".to_string()]).unwrap_err();
+        assert_eq!(error, "unknown column \"colour\"");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_root_user_and_group_names() {
+        assert_eq!(super::user_name(0), "root");
+        // Unknown ids fall back to the number
+        assert_eq!(super::user_name(4_000_000_000), "4000000000");
+    }
+}
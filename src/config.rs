@@ -2,15 +2,31 @@ use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
 
+use crate::error::LsaError;
+
 #[derive(Serialize, Deserialize)]
+#[serde(default)]
 pub struct Config {
     pub default_theme: String,
+    /// Columns of the directory table when `--columns` is not given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub columns: Option<Vec<String>>,
+    /// Directory names the tree shows as one summary line unless expanded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub collapse: Option<Vec<String>>,
 }
 
+/// Directories collapsed in the tree when config.toml has no `collapse`, each
+/// with the file that has to sit next to it, if any.
+pub const DEFAULT_COLLAPSED: [(&str, Option<&str>); 3] =
+    [(".git", None), ("node_modules", None), ("target", Some("Cargo.toml"))];
+
 impl Default for Config {
     fn default() -> Self {
         Config {
             default_theme: "catppuccin".to_string(),
+            columns: None,
+            collapse: None,
         }
     }
 }
@@ -27,10 +43,7 @@ pub fn load_config() -> Config {
 
     if config_path.exists() {
         match fs::read_to_string(&config_path) {
-            Ok(content) => match toml::from_str(&content) {
-                Ok(config) => config,
-                Err(_) => Config::default(),
-            },
+            Ok(content) => toml::from_str(&content).unwrap_or_default(),
             Err(_) => Config::default(),
         }
     } else {
@@ -38,14 +51,14 @@ pub fn load_config() -> Config {
     }
 }
 
-pub fn save_config(config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+pub fn save_config(config: &Config) -> Result<(), LsaError> {
     let config_path = get_config_path();
 
     if let Some(parent) = config_path.parent() {
-        fs::create_dir_all(parent)?;
+        fs::create_dir_all(parent).map_err(|e| LsaError::io(parent, e))?;
     }
 
-    let content = toml::to_string_pretty(config)?;
-    fs::write(config_path, content)?;
+    let content = toml::to_string_pretty(config).map_err(|e| LsaError::Config(e.to_string()))?;
+    fs::write(&config_path, content).map_err(|e| LsaError::io(&config_path, e))?;
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,180 @@
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use ignore::{WalkBuilder, WalkState};
+use lazy_static::lazy_static;
+
+lazy_static! {
+    static ref INTERRUPTED: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
+}
+
+/// Total size of a directory tree. `partial` is set when part of the tree
+/// could not be read (permission denied, vanished entries) or the walk was
+/// interrupted, in which case `bytes` is a lower bound.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DirSize {
+    pub bytes: u64,
+    pub partial: bool,
+}
+
+/// How recursive listings and the tree walk into directories.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Traversal {
+    // Descend into symlinked directories instead of showing them as leaves
+    pub follow: bool,
+    // Stay on the file system of the listed directory
+    pub one_file_system: bool,
+}
+
+/// Makes the first Ctrl-C stop any running size calculation instead of killing
+/// the process, so whatever was gathered so far can still be printed. A second
+/// Ctrl-C terminates as usual.
+pub fn install_interrupt_handler() {
+    use signal_hook::consts::SIGINT;
+    use signal_hook::flag;
+
+    let _ = flag::register_conditional_shutdown(SIGINT, 130, INTERRUPTED.clone());
+    let _ = flag::register(SIGINT, INTERRUPTED.clone());
+}
+
+pub fn was_interrupted() -> bool {
+    INTERRUPTED.load(Ordering::Relaxed)
+}
+
+/// Recursively sums the size of every file below `dir` using a parallel walker.
+/// Symlinks are only followed with `traversal.follow`. Files with several
+/// hard links and directories reached through several symlinks are only
+/// counted once, like `du -L`.
+pub fn dir_size(dir: &Path, traversal: Traversal) -> DirSize {
+    let total = AtomicU64::new(0);
+    let partial = AtomicBool::new(false);
+    let seen_inodes = Mutex::new(HashSet::new());
+
+    WalkBuilder::new(dir)
+        .standard_filters(false)
+        .follow_links(traversal.follow)
+        .same_file_system(traversal.one_file_system)
+        .build_parallel()
+        .run(|| {
+            Box::new(|result| {
+                if was_interrupted() {
+                    partial.store(true, Ordering::Relaxed);
+                    return WalkState::Quit;
+                }
+
+                let entry = match result {
+                    Ok(entry) => entry,
+                    // A loop leads back to what is already counted
+                    Err(err) if is_loop(&err) => return WalkState::Continue,
+                    Err(_) => {
+                        partial.store(true, Ordering::Relaxed);
+                        return WalkState::Continue;
+                    }
+                };
+
+                let is_dir = entry.file_type().is_some_and(|ft| ft.is_dir());
+                let metadata = match entry.metadata() {
+                    Ok(metadata) => metadata,
+                    Err(_) => {
+                        partial.store(true, Ordering::Relaxed);
+                        return WalkState::Continue;
+                    }
+                };
+
+                #[cfg(unix)]
+                {
+                    use std::os::unix::fs::MetadataExt;
+                    // Followed symlinks can lead to any directory or file twice
+                    if is_dir || traversal.follow || metadata.nlink() > 1 {
+                        let mut seen = seen_inodes.lock().unwrap();
+                        if !seen.insert((metadata.dev(), metadata.ino())) {
+                            return if is_dir { WalkState::Skip } else { WalkState::Continue };
+                        }
+                    }
+                }
+                #[cfg(not(unix))]
+                let _ = &seen_inodes;
+
+                if is_dir {
+                    return WalkState::Continue;
+                }
+
+                total.fetch_add(metadata.len(), Ordering::Relaxed);
+                WalkState::Continue
+            })
+        });
+
+    DirSize {
+        bytes: total.into_inner(),
+        partial: partial.into_inner(),
+    }
+}
+
+/// Whether a walk error is a symlink loop rather than something unreadable.
+fn is_loop(err: &ignore::Error) -> bool {
+    match err {
+        ignore::Error::Loop { .. } => true,
+        ignore::Error::WithPath { err, .. } | ignore::Error::WithDepth { err, .. } => is_loop(err),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DirSize, Traversal, dir_size};
+    use std::fs;
+
+    #[test]
+    fn test_dir_size_sums_nested_files() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        fs::create_dir(dir.path().join("nested")).unwrap();
+        fs::write(dir.path().join("a.txt"), vec![0u8; 100]).unwrap();
+        fs::write(dir.path().join("nested").join("b.txt"), vec![0u8; 50]).unwrap();
+
+        let size = dir_size(dir.path(), Traversal::default());
+        assert_eq!(size.bytes, 150);
+        assert!(!size.partial);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_dir_size_counts_hard_links_once() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        fs::write(dir.path().join("a.txt"), vec![0u8; 100]).unwrap();
+        fs::hard_link(dir.path().join("a.txt"), dir.path().join("b.txt")).unwrap();
+
+        assert_eq!(dir_size(dir.path(), Traversal::default()).bytes, 100);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_dir_size_follows_links_past_loops() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        fs::create_dir(dir.path().join("sub")).unwrap();
+        fs::write(dir.path().join("sub/a.txt"), vec![0u8; 100]).unwrap();
+        std::os::unix::fs::symlink("..", dir.path().join("sub/up")).unwrap();
+
+        let follow = Traversal {
+            follow: true,
+            one_file_system: false,
+        };
+        assert_eq!(dir_size(dir.path(), follow), DirSize { bytes: 100, partial: false });
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_dir_size_counts_linked_directories_once() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        fs::create_dir(dir.path().join("real")).unwrap();
+        fs::write(dir.path().join("real/f"), vec![0u8; 1000]).unwrap();
+        std::os::unix::fs::symlink("real", dir.path().join("link")).unwrap();
+
+        let follow = Traversal {
+            follow: true,
+            one_file_system: false,
+        };
+        assert_eq!(dir_size(dir.path(), follow), DirSize { bytes: 1000, partial: false });
+    }
+}
@@ -1,31 +1,154 @@
 use comfy_table::{
-    Attribute, Cell, Color, ContentArrangement, Table, modifiers::UTF8_ROUND_CORNERS,
+    Attribute, Cell, ContentArrangement, Table, modifiers::UTF8_ROUND_CORNERS,
     presets::UTF8_BORDERS_ONLY,
 };
+use globset::GlobMatcher;
+use ignore::WalkBuilder;
+use serde_json::{Value, json};
+use std::collections::HashSet;
 use std::env;
 use std::fs;
+use std::io;
 #[cfg(unix)]
 use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
 use std::process::Command;
-
-use crate::icons::get_file_icon;
+use std::time::{Duration, SystemTime};
+
+use crate::columns::{Column, DEFAULT_COLUMNS, Details};
+use crate::color::{cell_color, fg, new_table, reset};
+use crate::dirsize::{DirSize, Traversal, dir_size, was_interrupted};
+use crate::error::{LsaError, describe_io_error, report_minor_problem, report_serious_problem};
+use crate::git::{GitRepo, GitStatus};
+use crate::grid::{GridCell, GridOrder, display_width, render_grid, terminal_width};
+use crate::file_kind::FileKind;
+use crate::filter::{EntryFilter, EntryInfo, HiddenFiles};
+use crate::icons::{get_file_icon, icon_for};
+use crate::output::{OutputFormat, Records, print_records};
 use crate::parser::{parse_file, DataValue};
-use crate::themes::Theme;
-use crate::utils::{colorize_borders, format_permissions, format_size, format_time};
+use crate::sort::{DEFAULT_LIST_ORDER, DEFAULT_TREE_ORDER, SortOptions};
+use crate::theme_lint::{Level, LintReport};
+use crate::themes::{SLOT_NAMES, Theme};
+use crate::utils::{
+    colorize_borders, format_octal, format_permissions, format_rfc3339, format_size, format_time,
+};
+
+const ENTRY_COLUMNS: [&str; 18] = [
+    "path",
+    "name",
+    "type",
+    "size",
+    "size_partial",
+    "target",
+    "modified",
+    "accessed",
+    "changed",
+    "created",
+    "mode",
+    "permissions",
+    "owner",
+    "group",
+    "inode",
+    "links",
+    "git",
+    "error",
+];
+
+const TREE_COLUMNS: [&str; 19] = [
+    "depth",
+    "path",
+    "name",
+    "type",
+    "size",
+    "size_partial",
+    "target",
+    "modified",
+    "accessed",
+    "changed",
+    "created",
+    "mode",
+    "permissions",
+    "owner",
+    "group",
+    "inode",
+    "links",
+    "git",
+    "error",
+];
+
+/// Machine-readable form of a directory entry, matching `ENTRY_COLUMNS`
+/// without the trailing `error`. `size` is `None` for directories whose size
+/// was not calculated.
+fn entry_record(
+    path: &Path,
+    name: &str,
+    metadata: &fs::Metadata,
+    size: Option<u64>,
+    size_partial: bool,
+    target: Option<&Path>,
+    git: Option<GitStatus>,
+) -> Vec<Value> {
+    let full_path = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    let file_type = FileKind::of(path).id();
+    let modified = metadata.modified().ok().map(format_rfc3339);
+    let details = Details::read(metadata);
+
+    #[cfg(unix)]
+    let (mode, permissions) = {
+        let mode = metadata.permissions().mode();
+        (json!(format_octal(mode)), json!(format_permissions(mode)))
+    };
+    #[cfg(windows)]
+    let (mode, permissions) = (Value::Null, json!(format_permissions(metadata)));
+
+    vec![
+        json!(full_path.to_string_lossy()),
+        json!(name),
+        json!(file_type),
+        json!(size),
+        json!(size_partial),
+        json!(target.map(|t| t.to_string_lossy())),
+        json!(modified),
+        json!(details.accessed.map(format_rfc3339)),
+        json!(details.changed.map(format_rfc3339)),
+        json!(details.created.map(format_rfc3339)),
+        mode,
+        permissions,
+        json!(details.owner),
+        json!(details.group),
+        json!(details.inode),
+        json!(details.links),
+        json!(git.map(|status| status.label())),
+    ]
+}
 
-pub fn show_cpu_info(theme: &Theme) {
+/// Record for an entry whose metadata could not be read. Only the name and
+/// the error are known.
+fn error_record(path: &Path, name: &str, error: &str) -> Vec<Value> {
+    let full_path = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    let mut row = vec![json!(full_path.to_string_lossy()), json!(name)];
+    row.extend(std::iter::repeat_n(Value::Null, ENTRY_COLUMNS.len() - 3));
+    row.push(json!(error));
+    row
+}
+
+pub fn show_cpu_info(theme: &Theme, format: OutputFormat) -> Result<(), LsaError> {
     let output = match Command::new("lscpu").output() {
         Ok(output) => {
             if output.status.success() {
                 String::from_utf8_lossy(&output.stdout).to_string()
             } else {
-                eprintln!("Error running lscpu command");
-                return;
+                return Err(LsaError::Command {
+                    program: "lscpu",
+                    message: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+                });
             }
         }
         Err(e) => {
-            eprintln!("Failed to execute lscpu: {}", e);
-            return;
+            return Err(LsaError::Command {
+                program: "lscpu",
+                message: describe_io_error(&e),
+            });
         }
     };
 
@@ -39,7 +162,16 @@ pub fn show_cpu_info(theme: &Theme) {
         }
     }
 
-    let mut table = Table::new();
+    if !format.is_table() {
+        let mut records = Records::new(&["key", "value"]);
+        for (key, value) in &cpu_info {
+            records.push(vec![json!(key), json!(value)]);
+        }
+        print_records(&records, format);
+        return Ok(());
+    }
+
+    let mut table = new_table();
     table
         .load_preset(UTF8_BORDERS_ONLY)
         .apply_modifier(UTF8_ROUND_CORNERS)
@@ -47,47 +179,23 @@ pub fn show_cpu_info(theme: &Theme) {
         .set_header(vec![
             Cell::new("CPU Information")
                 .add_attribute(Attribute::Bold)
-                .fg(Color::Rgb {
-                    r: theme.header.0,
-                    g: theme.header.1,
-                    b: theme.header.2,
-                }),
+                .fg(cell_color(theme.header)),
             Cell::new("Value")
                 .add_attribute(Attribute::Bold)
-                .fg(Color::Rgb {
-                    r: theme.header.0,
-                    g: theme.header.1,
-                    b: theme.header.2,
-                }),
+                .fg(cell_color(theme.header)),
         ]);
 
     for (i, (key, value)) in cpu_info.iter().enumerate() {
         let key_color = if i % 2 == 0 {
-            Color::Rgb {
-                r: theme.file_name.0,
-                g: theme.file_name.1,
-                b: theme.file_name.2,
-            }
+            cell_color(theme.file_name)
         } else {
-            Color::Rgb {
-                r: theme.dir_name.0,
-                g: theme.dir_name.1,
-                b: theme.dir_name.2,
-            }
+            cell_color(theme.dir_name)
         };
 
         let value_color = if i % 2 == 0 {
-            Color::Rgb {
-                r: theme.file_size.0,
-                g: theme.file_size.1,
-                b: theme.file_size.2,
-            }
+            cell_color(theme.file_size)
         } else {
-            Color::Rgb {
-                r: theme.modified.0,
-                g: theme.modified.1,
-                b: theme.modified.2,
-            }
+            cell_color(theme.modified)
         };
 
         table.add_row(vec![
@@ -99,10 +207,11 @@ pub fn show_cpu_info(theme: &Theme) {
     let table_output = table.to_string();
     let colored_output = colorize_borders(&table_output, theme);
     println!("{}", colored_output);
+    Ok(())
 }
 
 pub fn show_help(theme: &Theme) {
-    let mut table = Table::new();
+    let mut table = new_table();
     table
         .load_preset(UTF8_BORDERS_ONLY)
         .apply_modifier(UTF8_ROUND_CORNERS)
@@ -110,25 +219,13 @@ pub fn show_help(theme: &Theme) {
         .set_header(vec![
             Cell::new("Option")
                 .add_attribute(Attribute::Bold)
-                .fg(Color::Rgb {
-                    r: theme.header.0,
-                    g: theme.header.1,
-                    b: theme.header.2,
-                }),
+                .fg(cell_color(theme.header)),
             Cell::new("Short")
                 .add_attribute(Attribute::Bold)
-                .fg(Color::Rgb {
-                    r: theme.header.0,
-                    g: theme.header.1,
-                    b: theme.header.2,
-                }),
+                .fg(cell_color(theme.header)),
             Cell::new("Description")
                 .add_attribute(Attribute::Bold)
-                .fg(Color::Rgb {
-                    r: theme.header.0,
-                    g: theme.header.1,
-                    b: theme.header.2,
-                }),
+                .fg(cell_color(theme.header)),
         ]);
 
     let options = vec![
@@ -159,18 +256,73 @@ pub fn show_help(theme: &Theme) {
             "",
             "Maximum size limit for workspace output",
         ),
-        ("--tree", "-t", "Show directory tree view"),
+        ("--tree", "-t", "Show directory tree view of the current directory or each PATH"),
+        (
+            "--sizes",
+            "",
+            "Tree: show file sizes and summed directory sizes, with a summary footer",
+        ),
+        (
+            "--counts",
+            "",
+            "Tree: show files and subdirectories per directory, with a summary footer",
+        ),
+        (
+            "--match <GLOB>",
+            "",
+            "Tree: only matching files and the directories leading to them (a / matches the path)",
+        ),
+        (
+            "--prune",
+            "",
+            "Tree: leave out directories that end up empty",
+        ),
+        (
+            "--dirs-only",
+            "",
+            "Tree: show only directories",
+        ),
+        (
+            "--max-entries <N>",
+            "",
+            "Tree: show at most N entries per directory and summarise the rest",
+        ),
+        (
+            "--expand <NAMES>",
+            "",
+            "Tree: show the contents of directories collapsed by config.toml `collapse` (default: .git, node_modules, target next to a Cargo.toml)",
+        ),
         ("--depth <NUM>", "-d", "Maximum depth for tree view"),
         (
             "--all",
             "-a",
-            "Show all files including hidden ones (for tree)",
+            "Show hidden files, plus . and .. in tables and grids",
+        ),
+        (
+            "--almost-all",
+            "-A",
+            "Show hidden files without . and ..",
         ),
         ("--path", "", "Show PATH environment variable directories"),
         (
             "--theme [NAME]",
             "",
-            "Set theme as default (interactive if no name)",
+            "Set theme as default (interactive picker with live preview if no name); user themes are read from ~/.config/lsa/themes",
+        ),
+        (
+            "theme import <FILE>",
+            "",
+            "Save a base16/base24, Alacritty, kitty or iTerm2 scheme as a user theme (--name, --from, --force)",
+        ),
+        (
+            "theme gallery",
+            "",
+            "Print a sample listing and tree in every theme",
+        ),
+        (
+            "theme lint [NAME]",
+            "",
+            "Check contrast and colorblind-safe slots (--background <COLOR>, --fix prints an adjusted theme)",
         ),
         (
             "--open <FILE>",
@@ -178,53 +330,149 @@ pub fn show_help(theme: &Theme) {
             "Parse and display structured data (JSON, YAML, TOML)",
         ),
         (
-            "--sort <SORT_BY>",
+            "--sort <KEYS>",
+            "",
+            "Sort by: name, size, modified, type, extension; combine with commas, e.g. type,size (default: name, tree: type,name)",
+        ),
+        (
+            "--grid[=ORDER]",
+            "",
+            "Show names in columns like ls, filled down (default) or across",
+        ),
+        (
+            "--recursive",
+            "-R",
+            "List everything below the directory in one table, named by relative path",
+        ),
+        (
+            "--git-ignore",
+            "",
+            "Hide paths excluded by .gitignore, .git/info/exclude or the global excludes",
+        ),
+        (
+            "--limit <N>",
+            "",
+            "Show only the first N entries after sorting",
+        ),
+        (
+            "--follow",
+            "",
+            "Descend into symlinked directories with --recursive, --tree or --dir-sizes, stopping at loops",
+        ),
+        (
+            "--one-file-system",
+            "",
+            "Do not descend into directories on other file systems with --recursive, --tree or --dir-sizes",
+        ),
+        (
+            "--reverse",
+            "-r",
+            "Reverse the sort order",
+        ),
+        (
+            "--natural",
+            "",
+            "Sort numbers in names by value (file2 before file10)",
+        ),
+        (
+            "--ignore-case",
+            "",
+            "Sort names case-insensitively",
+        ),
+        (
+            "--format <FORMAT>",
             "",
-            "Sort by: name, size, modified, type",
+            "Output format: table, json, ndjson, csv, tsv",
+        ),
+        (
+            "--color <WHEN>",
+            "",
+            "Colorize output: auto, always, never (honours NO_COLOR)",
+        ),
+        (
+            "--color-depth <DEPTH>",
+            "",
+            "Override detected color depth: truecolor, 256, 16",
+        ),
+        (
+            "--dereference",
+            "-L",
+            "Use the size, time and permissions of symlink targets",
+        ),
+        (
+            "--no-git",
+            "",
+            "Hide the git status column/markers inside a repository",
+        ),
+        (
+            "--dir-sizes",
+            "",
+            "Calculate recursive directory sizes (≥ marks partial totals)",
+        ),
+        (
+            "--name <GLOB>",
+            "",
+            "Only show entries whose name matches the glob (repeatable); in the tree, filters keep all directories",
+        ),
+        (
+            "--regex <REGEX>",
+            "",
+            "Only show entries whose name matches the regular expression",
+        ),
+        (
+            "--type <TYPE>",
+            "",
+            "Only show entries of type: file, dir, symlink, executable (comma separated)",
+        ),
+        (
+            "--min-size <SIZE>",
+            "",
+            "Only show entries of at least SIZE, e.g. 512, 10K, 1.5M (directories need --dir-sizes)",
+        ),
+        (
+            "--max-entry-size <SIZE>",
+            "",
+            "Only show entries of at most SIZE, e.g. 512, 10K, 1.5M (directories need --dir-sizes)",
+        ),
+        (
+            "--newer <AGE>",
+            "",
+            "Only show entries modified within AGE, e.g. 30m, 2d, 6mo, 1y",
+        ),
+        (
+            "--older <AGE>",
+            "",
+            "Only show entries modified more than AGE ago",
+        ),
+        (
+            "--where <EXPR>",
+            "",
+            "Only show entries matching EXPR: name/ext/perms (= != ~ =~), size/modified/age/mode (= != < <= > >=), type, `in (..)`, and/or/not, flags hidden/file/dir/symlink/executable. `age < 7d` and `modified < 7d` mean newer than 7 days, `modified < 2024-01-01` older than that date",
+        ),
+        (
+            "--columns <LIST>",
+            "",
+            "Table columns: index,name,type,size,mtime,atime,ctime,btime,permissions,octal,owner,group,inode,links (default: config.toml `columns`)",
         ),
     ];
 
     for (i, (long, short, desc)) in options.iter().enumerate() {
         let option_color = if i % 2 == 0 {
-            Color::Rgb {
-                r: theme.file_name.0,
-                g: theme.file_name.1,
-                b: theme.file_name.2,
-            }
+            cell_color(theme.file_name)
         } else {
-            Color::Rgb {
-                r: theme.dir_name.0,
-                g: theme.dir_name.1,
-                b: theme.dir_name.2,
-            }
+            cell_color(theme.dir_name)
         };
 
         let short_color = if i % 2 == 0 {
-            Color::Rgb {
-                r: theme.file_type.0,
-                g: theme.file_type.1,
-                b: theme.file_type.2,
-            }
+            cell_color(theme.file_type)
         } else {
-            Color::Rgb {
-                r: theme.dir_type.0,
-                g: theme.dir_type.1,
-                b: theme.dir_type.2,
-            }
+            cell_color(theme.dir_type)
         };
 
         let desc_color = if i % 2 == 0 {
-            Color::Rgb {
-                r: theme.file_size.0,
-                g: theme.file_size.1,
-                b: theme.file_size.2,
-            }
+            cell_color(theme.file_size)
         } else {
-            Color::Rgb {
-                r: theme.modified.0,
-                g: theme.modified.1,
-                b: theme.modified.2,
-            }
+            cell_color(theme.modified)
         };
 
         table.add_row(vec![
@@ -234,11 +482,8 @@ pub fn show_help(theme: &Theme) {
         ]);
     }
 
-    let title_color = format!(
-        "\x1b[38;2;{};{};{}m",
-        theme.header.0, theme.header.1, theme.header.2
-    );
-    let reset_color = "\x1b[0m";
+    let title_color = fg(theme.header);
+    let reset_color = reset();
 
     println!(
         "{}lsa - A colorful directory listing tool{}",
@@ -252,10 +497,7 @@ pub fn show_help(theme: &Theme) {
 
     println!();
     println!("{}Examples:{}", title_color, reset_color);
-    let example_color = format!(
-        "\x1b[38;2;{};{};{}m",
-        theme.file_name.0, theme.file_name.1, theme.file_name.2
-    );
+    let example_color = fg(theme.file_name);
     println!(
         "  {}lsa{}                              # Show current directory listing",
         example_color, reset_color
@@ -264,10 +506,54 @@ pub fn show_help(theme: &Theme) {
         "  {}lsa src{}                          # Show specific directory listing",
         example_color, reset_color
     );
+    println!(
+        "  {}lsa src tests Cargo.toml{}         # Files in one table, a titled table per directory",
+        example_color, reset_color
+    );
+    println!(
+        "  {}lsa 'src/**/*.rs'{}                # Expand globs without the shell",
+        example_color, reset_color
+    );
+    println!(
+        "  {}lsa --dir-sizes --sort size{}      # Show directories by their total size",
+        example_color, reset_color
+    );
+    println!(
+        "  {}lsa -R --git-ignore --type file --sort size -r --limit 20{}  # Largest files in the repo",
+        example_color, reset_color
+    );
+    println!(
+        "  {}lsa --grid=across --natural{}      # Compact listing, filled row by row",
+        example_color, reset_color
+    );
+    println!(
+        "  {}lsa --sort type,size -r{}          # Largest files first, directories last",
+        example_color, reset_color
+    );
     println!(
         "  {}lsa --tree{}                       # Show tree view",
         example_color, reset_color
     );
+    println!(
+        "  {}lsa -t src tests{}                 # One tree per directory",
+        example_color, reset_color
+    );
+    println!(
+        "  {}lsa -t --match '*.rs'{}            # Rust files and the directories leading to them",
+        example_color, reset_color
+    );
+    println!(
+        "  {}lsa -t -a --max-entries 20 --expand target{}",
+        example_color, reset_color
+    );
+    println!(
+        "  {}lsa -t -d 2 --sizes --counts{}     # Themed du: where does the space go",
+        example_color, reset_color
+    );
+    println!(
+        "  {}lsa -t -d 3 --follow --one-file-system{}  # Walk through symlinks, stay on this disk",
+        example_color, reset_color
+    );
     println!(
         "  {}lsa --path{}                       # Show PATH environment directories",
         example_color, reset_color
@@ -302,10 +588,46 @@ pub fn show_help(theme: &Theme) {
         "  {}lsa --theme{}                      # Set default theme interactively",
         example_color, reset_color
     );
+    println!(
+        "  {}lsa theme import nord.yaml{}       # Import a base16 scheme as a user theme",
+        example_color, reset_color
+    );
+    println!(
+        "  {}lsa theme gallery | less -R{}      # Compare every theme on a sample listing",
+        example_color, reset_color
+    );
+    println!(
+        "  {}lsa theme lint --background '#fdf6e3'{} # Check the default theme on a light terminal",
+        example_color, reset_color
+    );
+    println!(
+        "  {}lsa --type file --min-size 10M{}   # Files of 10 MB or more",
+        example_color, reset_color
+    );
+    println!(
+        "  {}lsa -t --name '*.rs' --newer 2d{}  # Rust files changed in the last two days",
+        example_color, reset_color
+    );
+    println!(
+        "  {}lsa --where 'size > 1M and ext in (rs, toml) and modified < 7d and not hidden'{}",
+        example_color, reset_color
+    );
+    println!(
+        "  {}lsa --where 'age < 7d or modified < 2024-01-01'{}  # Changed this week, or before 2024",
+        example_color, reset_color
+    );
+    println!(
+        "  {}lsa --columns name,size,owner,group,octal{}",
+        example_color, reset_color
+    );
     println!(
         "  {}lsa --cpu{}                        # Show CPU information",
         example_color, reset_color
     );
+    println!(
+        "  {}lsa --format json{}                # Print the listing as JSON for scripts",
+        example_color, reset_color
+    );
     println!(
         "  {}lsa --open config.json{}           # Display JSON file in tabular format",
         example_color, reset_color
@@ -318,57 +640,716 @@ pub fn show_help(theme: &Theme) {
         "  {}lsa --open Cargo.toml{}            # Display TOML file in tabular format",
         example_color, reset_color
     );
+
+    println!();
+    println!("{}Exit status:{}", title_color, reset_color);
+    println!("  0  OK");
+    println!("  1  Minor problems, e.g. an entry that could not be read (shown inline)");
+    println!("  2  Serious trouble, e.g. a directory or file that cannot be accessed");
 }
 
-pub fn show_directory_table(theme: &Theme, directory_path: Option<&str>, sort_by: Option<&crate::SortBy>) {
-    let target_dir = if let Some(path) = directory_path {
-        std::path::PathBuf::from(path)
-    } else {
-        env::current_dir().expect("Could not get current directory")
+/// How `show_listing` reads, filters and sorts the entries.
+pub struct ListOptions<'a> {
+    pub sort: &'a SortOptions,
+    pub dir_sizes: bool,
+    pub dereference: bool,
+    pub git: bool,
+    pub filter: &'a EntryFilter,
+    pub columns: &'a [Column],
+    // Names only, in as many columns as fit
+    pub grid: Option<GridOrder>,
+    pub hidden: HiddenFiles,
+    // Everything below each directory in one table, named by relative path
+    pub recursive: bool,
+    // Skip what .gitignore and friends exclude
+    pub git_ignore: bool,
+    // Keep only the first entries after sorting
+    pub limit: Option<usize>,
+    // How `--recursive` treats symlinked directories and mount points
+    pub traversal: Traversal,
+}
+
+/// Lists the paths given on the command line like `ls`: files together in
+/// one table, then the contents of each directory. With more than one path
+/// every directory gets its name as a title. A directory that cannot be read
+/// is reported and the others are still listed.
+pub fn show_listing(
+    theme: &Theme,
+    paths: &[PathBuf],
+    options: &ListOptions,
+    format: OutputFormat,
+) -> Result<(), LsaError> {
+    let (directories, files): (Vec<&PathBuf>, Vec<&PathBuf>) = paths
+        .iter()
+        .partition(|path| fs::metadata(path).is_ok_and(|metadata| metadata.is_dir()));
+
+    let mut file_entries: Vec<ListedEntry> = files
+        .iter()
+        .map(|path| {
+            let mut entry = read_entry(path.to_path_buf(), options.dir_sizes, options.dereference, options.traversal);
+            // Shown as given, like `ls src/main.rs`
+            entry.display_name = Some(path.to_string_lossy().to_string());
+            entry
+        })
+        .collect();
+    options
+        .sort
+        .sort(&mut file_entries, &DEFAULT_LIST_ORDER, ListedEntry::sort_info);
+    let discover = |dirs: Vec<&Path>| if options.git { discover_repos(dirs) } else { Vec::new() };
+    // Files given on the command line can come from different repositories
+    let file_dirs = || {
+        files
+            .iter()
+            .map(|path| match path.parent() {
+                Some(parent) if !parent.as_os_str().is_empty() => parent,
+                _ => Path::new("."),
+            })
+            .collect()
     };
 
-    let entries = fs::read_dir(&target_dir).expect("Could not read directory");
-
-    // Collect all entries with their metadata
-    let mut entries_with_meta: Vec<_> = entries
-        .filter_map(|entry| {
-            let entry = entry.ok()?;
-            let path = entry.path();
-            let metadata = path.metadata().ok()?;
-            let name = path.file_name()?.to_string_lossy().to_string();
-            let file_type = if path.is_dir() { "Directory" } else { "File" };
-             let size = if path.is_dir() {
-                 0 // Skip calculating directory sizes for performance
-             } else {
-                 metadata.len()
-             };
-            let modified = metadata.modified().ok()?;
-            Some((path, name, file_type.to_string(), size, modified, metadata))
+    if !format.is_table() {
+        let mut records = Records::new(&ENTRY_COLUMNS);
+        push_entry_records(&mut records, &file_entries, &discover(file_dirs()));
+        for dir in directories {
+            match read_directory(dir, options) {
+                Ok(entries) => push_entry_records(&mut records, &entries, &discover(vec![dir])),
+                Err(e) => report_serious_problem(&e),
+            }
+        }
+        print_records(&records, format);
+        return Ok(());
+    }
+
+    let titled = directories.len() + usize::from(!file_entries.is_empty()) > 1;
+    let mut first = true;
+    if !file_entries.is_empty() {
+        print_entries(theme, file_entries, &discover(file_dirs()), options);
+        first = false;
+    }
+    for dir in directories {
+        let entries = match read_directory(dir, options) {
+            Ok(entries) => entries,
+            Err(e) => {
+                report_serious_problem(&e);
+                continue;
+            }
+        };
+        if !first {
+            println!();
+        }
+        first = false;
+        if titled {
+            println!("{}{}:{}", fg(theme.dir_name), dir.display(), reset());
+        }
+        print_entries(theme, entries, &discover(vec![dir]), options);
+    }
+    Ok(())
+}
+
+/// The entries of `dir` that pass the filter, sorted.
+fn read_directory(dir: &Path, options: &ListOptions) -> Result<Vec<ListedEntry>, LsaError> {
+    let max_depth = (!options.recursive).then_some(1);
+    let mut paths = entry_paths(dir, max_depth, options.hidden, options.git_ignore, options.traversal)
+        .map_err(|e| LsaError::io(dir, e))?;
+    if options.hidden == HiddenFiles::All && !options.recursive {
+        paths.splice(0..0, [dir.join("."), dir.join("..")]);
+    }
+
+    // Collect all entries with their metadata. Symlinks are read with lstat so
+    // broken ones stay in the listing.
+    let mut entries_with_meta: Vec<ListedEntry> = paths
+        .into_iter()
+        .map(|path| {
+            // `..` would sum up the whole parent, `.` is the listed directory
+            let dir_sizes = options.dir_sizes && !path.ends_with("..");
+            let mut entry = read_entry(path, dir_sizes, options.dereference, options.traversal);
+            if entry.path.ends_with("..") {
+                entry.name = "..".to_string();
+            } else if entry.path == dir.join(".") {
+                entry.name = ".".to_string();
+            } else if options.recursive
+                && let Ok(relative) = entry.path.strip_prefix(dir)
+            {
+                entry.display_name = Some(relative.to_string_lossy().to_string());
+            }
+            entry
         })
+        .filter(|entry| options.filter.matches(&entry.info()))
         .collect();
 
-    // Sort entries based on sort_by parameter
-    if let Some(sort_by) = sort_by {
-        entries_with_meta.sort_by(|a, b| {
-            match sort_by {
-                crate::SortBy::Name => a.1.cmp(&b.1),
-                crate::SortBy::Size => a.3.cmp(&b.3),
-                crate::SortBy::Modified => a.4.cmp(&b.4),
-                crate::SortBy::Type => {
-                    // Directories first, then files
-                    let a_is_dir = a.0.is_dir();
-                    let b_is_dir = b.0.is_dir();
-                    match (a_is_dir, b_is_dir) {
-                        (true, false) => std::cmp::Ordering::Less,
-                        (false, true) => std::cmp::Ordering::Greater,
-                        _ => a.1.cmp(&b.1), // Same type, sort by name
-                    }
+    if options.dir_sizes && was_interrupted() {
+        eprintln!("Interrupted: directory sizes are incomplete");
+    }
+
+    options
+        .sort
+        .sort(&mut entries_with_meta, &DEFAULT_LIST_ORDER, ListedEntry::sort_info);
+    if let Some(limit) = options.limit {
+        entries_with_meta.truncate(limit);
+    }
+    Ok(entries_with_meta)
+}
+
+/// Paths of the entries of `dir`, or of everything below it up to
+/// `max_depth`, that a listing shows. Dotfiles are skipped unless asked for,
+/// and with `git_ignore` so is what git ignores. Symlinks are listed but only
+/// walked into with `--follow`, where links back to a parent are reported
+/// instead. Only an unreadable `dir` is an error.
+fn entry_paths(
+    dir: &Path,
+    max_depth: Option<usize>,
+    hidden: HiddenFiles,
+    git_ignore: bool,
+    traversal: Traversal,
+) -> io::Result<Vec<PathBuf>> {
+    let entries = fs::read_dir(dir)?;
+    let show_dotfiles = hidden.shows_dotfiles();
+    if max_depth == Some(1) && !git_ignore {
+        return Ok(entries
+            .filter_map(|entry| match entry {
+                Ok(entry) => Some(entry.path()),
+                Err(e) => {
+                    eprintln!(
+                        "Warning: cannot read an entry of '{}': {}",
+                        dir.display(),
+                        describe_io_error(&e)
+                    );
+                    report_minor_problem();
+                    None
                 }
+            })
+            .filter(|path| show_dotfiles || !is_dotfile(path))
+            .collect());
+    }
+
+    // The same rules as the workspace walk, including .gitignore files in
+    // parent directories
+    let walker = WalkBuilder::new(dir)
+        .standard_filters(false)
+        .hidden(!show_dotfiles)
+        .parents(git_ignore)
+        .git_ignore(git_ignore)
+        .git_exclude(git_ignore)
+        .git_global(git_ignore)
+        .max_depth(max_depth)
+        .follow_links(traversal.follow)
+        .same_file_system(traversal.one_file_system)
+        // Recursive listings leave out the repository internals
+        .filter_entry(move |entry| max_depth == Some(1) || entry.file_name() != ".git")
+        .build();
+
+    Ok(walker
+        .filter_map(|result| match result {
+            Ok(entry) => (entry.depth() > 0).then(|| entry.into_path()),
+            Err(err) => {
+                eprintln!("Warning: cannot walk '{}': {}", dir.display(), err);
+                report_minor_problem();
+                None
+            }
+        })
+        .collect())
+}
+
+fn is_dotfile(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+}
+
+fn push_entry_records(records: &mut Records, entries: &[ListedEntry], git_repos: &[GitRepo]) {
+    for entry in entries {
+        let Some(metadata) = &entry.metadata else {
+            let error = entry.error.as_deref().unwrap_or_default();
+            records.push(error_record(&entry.path, entry.shown_name(), error));
+            continue;
+        };
+        let mut row = entry_record(
+            &entry.path,
+            entry.shown_name(),
+            metadata,
+            entry.known_size(),
+            entry.dir_size.is_some_and(|d| d.partial),
+            entry.target.as_deref(),
+            git_status(git_repos, &entry.path),
+        );
+        row.push(Value::Null);
+        records.push(row);
+    }
+}
+
+/// Prints one table, or grid, of entries.
+fn print_entries(
+    theme: &Theme,
+    entries: Vec<ListedEntry>,
+    git_repos: &[GitRepo],
+    options: &ListOptions,
+) {
+    let rows: Vec<EntryRow> = entries
+        .into_iter()
+        .map(|entry| {
+            let git = git_status(git_repos, &entry.path);
+            let icon = entry.icon();
+            let name = entry.shown_name().to_string();
+            let Some(metadata) = entry.metadata else {
+                // Like `ls`, keep the name and mark everything else unknown
+                return EntryRow {
+                    icon,
+                    name,
+                    kind: entry.kind,
+                    target: None,
+                    size: "?".to_string(),
+                    modified: "?".to_string(),
+                    permissions: "?".to_string(),
+                    octal: "?".to_string(),
+                    details: Details::default(),
+                    git,
+                    error: entry.error,
+                };
+            };
+
+            let size = match entry.dir_size {
+                Some(DirSize { partial: true, .. }) => format!("≥ {}", format_size(entry.size)),
+                Some(_) => format_size(entry.size),
+                None if metadata.is_dir() => "-".to_string(),
+                None => format_size(entry.size),
+            };
+
+            #[cfg(unix)]
+            let (permissions, octal) = {
+                let mode = metadata.permissions().mode();
+                (format_permissions(mode), format_octal(mode))
+            };
+            #[cfg(windows)]
+            let (permissions, octal) = (format_permissions(&metadata), "-".to_string());
+
+            EntryRow {
+                icon,
+                git,
+                name,
+                kind: entry.kind,
+                target: entry.target.map(|t| t.to_string_lossy().to_string()),
+                size,
+                modified: entry.modified.map(format_time).unwrap_or_else(|| "?".to_string()),
+                permissions,
+                octal,
+                details: Details::read(&metadata),
+                error: None,
             }
-        });
+        })
+        .collect();
+
+    if let Some(order) = options.grid {
+        for line in render_entry_grid(theme, &rows, order, terminal_width()) {
+            println!("{line}");
+        }
+        return;
+    }
+    println!(
+        "{}",
+        render_entry_table(theme, &rows, options.columns, !git_repos.is_empty())
+    );
+}
+
+/// Git status of `path` in whichever of `repos` it belongs to.
+fn git_status(repos: &[GitRepo], path: &Path) -> Option<GitStatus> {
+    repos.iter().find_map(|repo| repo.lookup(path))
+}
+
+/// The work trees containing `dirs`, each read once.
+fn discover_repos<'a>(dirs: impl IntoIterator<Item = &'a Path>) -> Vec<GitRepo> {
+    let mut repos: Vec<GitRepo> = Vec::new();
+    for dir in dirs {
+        if repos.iter().all(|repo| repo.lookup(dir).is_none())
+            && let Some(repo) = GitRepo::discover(dir)
+        {
+            repos.push(repo);
+        }
+    }
+    repos
+}
+
+/// Reads one entry of a listing. An entry whose metadata cannot be read is
+/// kept with the error, so it still shows up in the output.
+fn read_entry(path: PathBuf, dir_sizes: bool, dereference: bool, traversal: Traversal) -> ListedEntry {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string_lossy().to_string());
+
+    let link_metadata = match fs::symlink_metadata(&path) {
+        Ok(metadata) => metadata,
+        Err(e) => {
+            report_minor_problem();
+            return ListedEntry {
+                path,
+                name,
+                display_name: None,
+                kind: FileKind::File,
+                target: None,
+                size: 0,
+                modified: None,
+                metadata: None,
+                dir_size: None,
+                error: Some(describe_io_error(&e)),
+            };
+        }
+    };
+
+    let kind = FileKind::from_metadata(&path, &link_metadata);
+    let target = kind.is_symlink().then(|| fs::read_link(&path).ok()).flatten();
+    let metadata = if dereference && kind.is_symlink() {
+        fs::metadata(&path).unwrap_or(link_metadata)
+    } else {
+        link_metadata
+    };
+    // Only walk symlinked directories when asked to follow links
+    let counts_as_dir = metadata.is_dir();
+    let dir_size = if counts_as_dir && dir_sizes {
+        Some(dir_size(&path, traversal))
+    } else {
+        None
+    };
+    if dir_size.is_some_and(|d| d.partial) && !was_interrupted() {
+        report_minor_problem();
+    }
+    let size = if counts_as_dir {
+        // Directory sizes are only calculated on request (--dir-sizes)
+        dir_size.map_or(0, |d| d.bytes)
+    } else {
+        metadata.len()
+    };
+    ListedEntry {
+        path,
+        name,
+        display_name: None,
+        kind,
+        target,
+        size,
+        modified: metadata.modified().ok(),
+        metadata: Some(metadata),
+        dir_size,
+        error: None,
+    }
+}
+
+/// A directory entry as read from disk. `metadata` belongs to the link itself
+/// unless links are dereferenced, and is `None` when it could not be read.
+struct ListedEntry {
+    path: PathBuf,
+    // The file name, which filters look at
+    name: String,
+    // What the listing shows instead, e.g. the path below the listed
+    // directory with --recursive
+    display_name: Option<String>,
+    kind: FileKind,
+    target: Option<PathBuf>,
+    size: u64,
+    modified: Option<SystemTime>,
+    metadata: Option<fs::Metadata>,
+    dir_size: Option<DirSize>,
+    // Why `metadata` is missing, e.g. "permission denied"
+    error: Option<String>,
+}
+
+impl ListedEntry {
+    fn icon(&self) -> &'static str {
+        icon_for(&self.name, self.kind)
+    }
+
+    fn shown_name(&self) -> &str {
+        self.display_name.as_deref().unwrap_or(&self.name)
+    }
+
+    fn info(&self) -> EntryInfo<'_> {
+        match &self.metadata {
+            Some(metadata) => EntryInfo::new(&self.name, self.kind, metadata, self.known_size()),
+            None => EntryInfo::unreadable(&self.name),
+        }
+    }
+
+    /// Like `info`, but named as shown so a recursive listing sorted by name
+    /// keeps each directory's entries together.
+    fn sort_info(&self) -> EntryInfo<'_> {
+        EntryInfo {
+            name: self.shown_name(),
+            ..self.info()
+        }
+    }
+
+    /// `size`, or `None` for directories whose size was not calculated.
+    fn known_size(&self) -> Option<u64> {
+        match &self.metadata {
+            Some(metadata) if metadata.is_dir() && self.dir_size.is_none() => None,
+            Some(_) => Some(self.size),
+            None => None,
+        }
+    }
+}
+
+/// One line of the directory table, already formatted. Kept apart from the
+/// filesystem so the theme previews can render made-up entries.
+struct EntryRow {
+    icon: &'static str,
+    name: String,
+    kind: FileKind,
+    // Where a symlink points, shown as `name → target`
+    target: Option<String>,
+    size: String,
+    modified: String,
+    permissions: String,
+    octal: String,
+    details: Details,
+    git: Option<GitStatus>,
+    // Shown in place of the type when the entry could not be read
+    error: Option<String>,
+}
+
+/// Icons and names laid out like `ls`, colored by entry type.
+fn render_entry_grid(theme: &Theme, rows: &[EntryRow], order: GridOrder, width: usize) -> Vec<String> {
+    let cells: Vec<GridCell> = rows
+        .iter()
+        .map(|entry| {
+            let color = if entry.error.is_some() || entry.kind == FileKind::BrokenSymlink {
+                theme.broken_link_color()
+            } else if entry.kind.is_symlink() {
+                theme.symlink_color()
+            } else if entry.kind.is_dir() {
+                theme.dir_name
+            } else {
+                theme.file_name
+            };
+            let name = format!("{} {}", entry.icon, entry.name);
+            GridCell {
+                width: display_width(&name),
+                text: format!("{}{}{}", fg(color), name, reset()),
+            }
+        })
+        .collect();
+    render_grid(&cells, width, order)
+}
+
+fn render_entry_table(
+    theme: &Theme,
+    rows: &[EntryRow],
+    columns: &[Column],
+    git_column: bool,
+) -> String {
+    let mut table = new_table();
+    table
+        .load_preset(UTF8_BORDERS_ONLY)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic);
+
+    let mut header: Vec<Cell> = columns
+        .iter()
+        .map(|column| {
+            Cell::new(column.header())
+                .add_attribute(Attribute::Bold)
+                .fg(cell_color(theme.header))
+        })
+        .collect();
+    if git_column {
+        header.push(
+            Cell::new("Git")
+                .add_attribute(Attribute::Bold)
+                .fg(cell_color(theme.header)),
+        );
     }
+    table.set_header(header);
+
+    for (row_number, entry) in (1..).zip(rows) {
+        let name_with_icon = match &entry.target {
+            Some(target) => format!("{} {} → {}", entry.icon, entry.name, target),
+            None => format!("{} {}", entry.icon, entry.name),
+        };
+        let (mut name_color, mut type_color, size_color) = if entry.kind.is_dir() {
+            (theme.dir_name, theme.dir_type, theme.dir_size)
+        } else {
+            (theme.file_name, theme.file_type, theme.file_size)
+        };
+        if entry.kind == FileKind::BrokenSymlink {
+            (name_color, type_color) = (theme.broken_link_color(), theme.broken_link_color());
+        } else if entry.kind.is_symlink() {
+            name_color = theme.symlink_color();
+        }
+
+        // Unreadable entries show `?` like `ls`, missing details `-`
+        let unknown = if entry.error.is_some() { "?" } else { "-" };
+        let text = |value: Option<String>| value.unwrap_or_else(|| unknown.to_string());
+        let time = |value: Option<SystemTime>| text(value.map(format_time));
+        let details = &entry.details;
+
+        let mut row: Vec<Cell> = columns
+            .iter()
+            .map(|column| {
+                let content = match column {
+                    Column::Index => row_number.to_string(),
+                    Column::Name => name_with_icon.clone(),
+                    Column::Type => match &entry.error {
+                        Some(error) => {
+                            return Cell::new(error).fg(cell_color(theme.broken_link_color()));
+                        }
+                        None => entry.kind.label().to_string(),
+                    },
+                    Column::Size => entry.size.clone(),
+                    Column::Mtime => entry.modified.clone(),
+                    Column::Atime => time(details.accessed),
+                    Column::Ctime => time(details.changed),
+                    Column::Btime => time(details.created),
+                    Column::Permissions => entry.permissions.clone(),
+                    Column::Octal => entry.octal.clone(),
+                    Column::Owner => text(details.owner.clone()),
+                    Column::Group => text(details.group.clone()),
+                    Column::Inode => text(details.inode.map(|inode| inode.to_string())),
+                    Column::Links => text(details.links.map(|links| links.to_string())),
+                };
+                let color = column.color(theme).unwrap_or(match column {
+                    Column::Name => name_color,
+                    Column::Type => type_color,
+                    _ => size_color,
+                });
+                Cell::new(content).fg(cell_color(color))
+            })
+            .collect();
+
+        if git_column {
+            let status = entry.git.unwrap_or(GitStatus::Clean);
+            row.push(Cell::new(status.marker()).fg(cell_color(status.color(theme))));
+        }
+
+        table.add_row(row);
+    }
+
+    colorize_borders(&table.to_string(), theme)
+}
+
+/// Shows the tree below each of `roots`. `.` stands for the working
+/// directory and is titled with its name. A root that is missing, not a
+/// directory or unreadable is reported and the other roots are still shown.
+pub fn show_tree(
+    theme: &Theme,
+    roots: &[PathBuf],
+    options: &TreeOptions,
+    format: OutputFormat,
+) -> Result<(), LsaError> {
+    let trees: Vec<RootTree> = roots
+        .iter()
+        .filter_map(|root| read_root(root, options).map_err(|e| report_serious_problem(&e)).ok())
+        .collect();
+
+    if options.annotations.sizes && was_interrupted() {
+        eprintln!("Interrupted: directory sizes are incomplete");
+    }
+    if !format.is_table() {
+        let mut records = Records::new(&TREE_COLUMNS);
+        for tree in &trees {
+            tree_records(&tree.nodes, 1, &mut records);
+        }
+        print_records(&records, format);
+    } else {
+        for line in tree_lines(theme, &trees, options.annotations) {
+            println!("{line}");
+        }
+    }
+    Ok(())
+}
 
-    let mut table = Table::new();
+/// One root of `show_tree` with everything below it.
+struct RootTree {
+    dir: PathBuf,
+    // What the root is titled with
+    name: String,
+    nodes: Vec<TreeNode>,
+    // Size of everything below the root and files directly inside it,
+    // taken before `--dirs-only`
+    size: DirSize,
+    files: usize,
+}
+
+/// Reads the tree below `root`. A root that is missing, not a directory or
+/// unreadable is an error.
+fn read_root(root: &Path, options: &TreeOptions) -> Result<RootTree, LsaError> {
+    let (dir, name) = if root.as_os_str() == "." {
+        let current_dir = env::current_dir().map_err(LsaError::CurrentDir)?;
+        let name = current_dir.file_name().unwrap_or_default().to_string_lossy().to_string();
+        (current_dir, name)
+    } else {
+        (root.to_path_buf(), root.display().to_string())
+    };
+
+    match fs::metadata(&dir) {
+        Ok(metadata) if metadata.is_dir() => {}
+        Ok(_) => {
+            return Err(LsaError::WrongKind {
+                path: dir,
+                expected: "directory",
+            });
+        }
+        Err(e) => return Err(LsaError::io(&dir, e)),
+    }
+    let git_repo = if options.git { GitRepo::discover(&dir) } else { None };
+    let unignored = options.git_ignore.then(|| unignored_paths(&dir, options));
+    let walk = TreeWalk::new(&dir, options, git_repo.as_ref(), unignored.as_ref());
+    let mut nodes = walk.collect().map_err(|e| LsaError::io(&dir, e))?;
+    let size = subtree_size(&nodes, false);
+    let files = nodes.iter().filter(|node| !node.kind.is_dir()).count();
+    if options.dirs_only {
+        nodes.retain(|node| node.kind.is_dir());
+    }
+    Ok(RootTree {
+        dir,
+        name,
+        nodes,
+        size,
+        files,
+    })
+}
+
+/// The lines of the table view: each root titled and followed by its tree,
+/// then the footer with `--sizes` or `--counts`.
+fn tree_lines(theme: &Theme, trees: &[RootTree], annotations: TreeAnnotations) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut summary = TreeSummary::default();
+    for tree in trees {
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+        lines.push(format!(
+            "{}{} {}{}",
+            fg(theme.dir_name),
+            get_file_icon(&tree.dir),
+            tree.name,
+            reset()
+        ));
+        render_tree(&tree.nodes, "", theme, annotations, &mut lines);
+        summary.add(tree);
+    }
+
+    if !trees.is_empty() && (annotations.sizes || annotations.counts) {
+        lines.push(String::new());
+        lines.push(summary.render(theme, annotations.sizes));
+    }
+    lines
+}
+
+pub fn show_path_table(theme: &Theme, format: OutputFormat) -> Result<(), LsaError> {
+    let path_env = env::var("PATH").map_err(|source| LsaError::Environment {
+        variable: "PATH",
+        source,
+    })?;
+
+    let path_dirs: Vec<&str> = path_env.split(':').collect();
+
+    if !format.is_table() {
+        let mut records = Records::new(&["index", "directory", "status"]);
+        for (index, path_dir) in path_dirs.iter().enumerate() {
+            let status = path_status(std::path::Path::new(path_dir));
+            records.push(vec![json!(index + 1), json!(path_dir), json!(status)]);
+        }
+        print_records(&records, format);
+        return Ok(());
+    }
+
+    let mut table = new_table();
     table
         .load_preset(UTF8_BORDERS_ONLY)
         .apply_modifier(UTF8_ROUND_CORNERS)
@@ -376,312 +1357,900 @@ pub fn show_directory_table(theme: &Theme, directory_path: Option<&str>, sort_by
         .set_header(vec![
             Cell::new("#")
                 .add_attribute(Attribute::Bold)
-                .fg(Color::Rgb {
-                    r: theme.header.0,
-                    g: theme.header.1,
-                    b: theme.header.2,
-                }),
-            Cell::new("Name")
-                .add_attribute(Attribute::Bold)
-                .fg(Color::Rgb {
-                    r: theme.header.0,
-                    g: theme.header.1,
-                    b: theme.header.2,
-                }),
-            Cell::new("Type")
-                .add_attribute(Attribute::Bold)
-                .fg(Color::Rgb {
-                    r: theme.header.0,
-                    g: theme.header.1,
-                    b: theme.header.2,
-                }),
-            Cell::new("Size")
-                .add_attribute(Attribute::Bold)
-                .fg(Color::Rgb {
-                    r: theme.header.0,
-                    g: theme.header.1,
-                    b: theme.header.2,
-                }),
-            Cell::new("Modified")
+                .fg(cell_color(theme.header)),
+            Cell::new("Directory")
                 .add_attribute(Attribute::Bold)
-                .fg(Color::Rgb {
-                    r: theme.header.0,
-                    g: theme.header.1,
-                    b: theme.header.2,
-                }),
-            Cell::new("Permissions")
+                .fg(cell_color(theme.header)),
+            Cell::new("Status")
                 .add_attribute(Attribute::Bold)
-                .fg(Color::Rgb {
-                    r: theme.header.0,
-                    g: theme.header.1,
-                    b: theme.header.2,
-                }),
+                .fg(cell_color(theme.header)),
         ]);
 
-    let mut row_number = 1;
-    for (path, name, file_type, size, modified, metadata) in entries_with_meta {
-        let name_with_icon = format!("{} {}", get_file_icon(&path), name);
-        let name_cell = if path.is_dir() {
-            Cell::new(&name_with_icon).fg(Color::Rgb {
-                r: theme.dir_name.0,
-                g: theme.dir_name.1,
-                b: theme.dir_name.2,
-            })
+    for (index, path_dir) in path_dirs.iter().enumerate() {
+        let path = std::path::Path::new(path_dir);
+        let exists = path.exists();
+        let is_dir = path.is_dir();
+        let is_symlink = path.is_symlink();
+
+        let status = path_status(path);
+
+        let dir_cell = if exists && is_dir {
+            let icon = if is_symlink {
+                "↪" 
+            } else {
+                get_file_icon(path)
+            };
+            Cell::new(format!("{} {}", icon, path_dir)).fg(cell_color(theme.dir_name))
+        } else if is_symlink {
+            Cell::new(format!("↪ {}", path_dir)).fg(cell_color(theme.permissions))
         } else {
-            Cell::new(&name_with_icon).fg(Color::Rgb {
-                r: theme.file_name.0,
-                g: theme.file_name.1,
-                b: theme.file_name.2,
-            })
+            Cell::new(format!("✗ {}", path_dir)).fg(cell_color(theme.file_name))
         };
 
-        let type_cell = if path.is_dir() {
-            Cell::new(&file_type).fg(Color::Rgb {
-                r: theme.dir_type.0,
-                g: theme.dir_type.1,
-                b: theme.dir_type.2,
-            })
-        } else {
-            Cell::new(&file_type).fg(Color::Rgb {
-                r: theme.file_type.0,
-                g: theme.file_type.1,
-                b: theme.file_type.2,
-            })
+        let status_cell = match status {
+            "OK" => Cell::new(status).fg(cell_color(theme.file_size)),
+            _ => Cell::new(status).fg(cell_color(theme.modified)),
         };
 
-        let size_cell = Cell::new(if path.is_dir() { "-".to_string() } else { format_size(size) }).fg(if path.is_dir() {
-            Color::Rgb {
-                r: theme.dir_size.0,
-                g: theme.dir_size.1,
-                b: theme.dir_size.2,
-            }
+        table.add_row(vec![
+            Cell::new((index + 1).to_string()).fg(cell_color(theme.row_number)),
+            dir_cell,
+            status_cell,
+        ]);
+    }
+
+    let table_output = table.to_string();
+    let colored_output = colorize_borders(&table_output, theme);
+    println!("{colored_output}");
+    Ok(())
+}
+
+fn path_status(path: &Path) -> &'static str {
+    let exists = path.exists();
+    let is_dir = path.is_dir();
+    let is_symlink = path.is_symlink();
+
+    if !exists {
+        if is_symlink {
+            "Broken symlink"
         } else {
-            Color::Rgb {
-                r: theme.file_size.0,
-                g: theme.file_size.1,
-                b: theme.file_size.2,
-            }
-        });
+            "Missing"
+        }
+    } else if is_symlink && is_dir {
+        "Symlink to directory"
+    } else if is_symlink {
+        "Symlink to file"
+    } else if !is_dir {
+        "Not a directory"
+    } else {
+        "OK"
+    }
+}
 
-        let modified_cell = Cell::new(format_time(modified)).fg(Color::Rgb {
-            r: theme.modified.0,
-            g: theme.modified.1,
-            b: theme.modified.2,
-        });
+fn hex_color(color: (u8, u8, u8)) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.0, color.1, color.2)
+}
 
-        let permissions_cell = {
-            #[cfg(unix)]
-            let perm_text = format_permissions(metadata.permissions().mode());
-            #[cfg(windows)]
-            let perm_text = format_permissions(&metadata);
+pub fn show_theme_lint(theme: &Theme, report: &LintReport, format: OutputFormat) {
+    if !format.is_table() {
+        let mut records = Records::new(&[
+            "check", "slot", "other", "color", "value", "minimum", "result",
+        ]);
+        for check in &report.slots {
+            records.push(vec![
+                json!("contrast"),
+                json!(check.slot),
+                Value::Null,
+                json!(hex_color(check.color)),
+                json!((check.contrast * 100.0).round() / 100.0),
+                json!(check.minimum),
+                json!(check.level.label()),
+            ]);
+        }
+        for pair in &report.pairs {
+            records.push(vec![
+                json!("distinct"),
+                json!(pair.first),
+                json!(pair.second),
+                Value::Null,
+                json!((pair.distance * 10.0).round() / 10.0),
+                json!(10.0),
+                json!(format!("confusable ({})", pair.vision)),
+            ]);
+        }
+        print_records(&records, format);
+        return;
+    }
 
-            Cell::new(perm_text).fg(Color::Rgb {
-                r: theme.permissions.0,
-                g: theme.permissions.1,
-                b: theme.permissions.2,
-            })
-        };
+    let header = |title: &str| {
+        Cell::new(title)
+            .add_attribute(Attribute::Bold)
+            .fg(cell_color(theme.header))
+    };
+
+    let mut table = new_table();
+    table
+        .load_preset(UTF8_BORDERS_ONLY)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec![
+            header("Slot"),
+            header("Color"),
+            header("Contrast"),
+            header("Status"),
+        ]);
 
+    for check in &report.slots {
+        let status_color = match check.level {
+            Level::Ok => theme.file_size,
+            Level::Warning => theme.modified,
+            Level::Error => theme.permissions,
+        };
         table.add_row(vec![
-            Cell::new(row_number.to_string()).fg(Color::Rgb {
-                r: theme.row_number.0,
-                g: theme.row_number.1,
-                b: theme.row_number.2,
-            }),
-            name_cell,
-            type_cell,
-            size_cell,
-            modified_cell,
-            permissions_cell,
+            Cell::new(check.slot).fg(cell_color(theme.row_number)),
+            Cell::new(format!("██ {}", hex_color(check.color))).fg(cell_color(check.color)),
+            Cell::new(format!("{:.2}:1 (min {}:1)", check.contrast, check.minimum))
+                .fg(cell_color(theme.file_type)),
+            Cell::new(check.level.label()).fg(cell_color(status_color)),
         ]);
-        row_number += 1;
     }
-
-    let table_output = table.to_string();
-    let colored_output = colorize_borders(&table_output, theme);
-    println!("{colored_output}");
+
+    let title_color = fg(theme.header);
+    let reset_color = reset();
+    println!(
+        "{}Theme '{}' on {}{}",
+        title_color,
+        theme.name,
+        hex_color(report.background),
+        reset_color
+    );
+    println!("{}", colorize_borders(&table.to_string(), theme));
+
+    if !report.pairs.is_empty() {
+        let mut pairs = new_table();
+        pairs
+            .load_preset(UTF8_BORDERS_ONLY)
+            .apply_modifier(UTF8_ROUND_CORNERS)
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_header(vec![
+                header("Slots"),
+                header("Meaning"),
+                header("Look alike for"),
+                header("ΔE"),
+            ]);
+        for pair in &report.pairs {
+            pairs.add_row(vec![
+                Cell::new(format!("{} / {}", pair.first, pair.second))
+                    .fg(cell_color(theme.row_number)),
+                Cell::new(pair.meaning).fg(cell_color(theme.file_name)),
+                Cell::new(pair.vision).fg(cell_color(theme.modified)),
+                Cell::new(format!("{:.1}", pair.distance)).fg(cell_color(theme.file_type)),
+            ]);
+        }
+        println!("{}", colorize_borders(&pairs.to_string(), theme));
+    }
+
+    let problems = report.slots.iter().filter(|s| s.level != Level::Ok).count()
+        + report.pairs.len();
+    if problems == 0 {
+        println!("{}✓ No problems found{}", fg(theme.file_size), reset_color);
+    } else {
+        println!(
+            "{}✗ {} problem(s) found, run with --fix to print an adjusted theme{}",
+            fg(theme.permissions),
+            problems,
+            reset_color
+        );
+    }
+}
+
+/// A small made-up project rendered as table and tree, used to preview themes
+/// without touching the filesystem.
+pub fn render_theme_preview(theme: &Theme) -> String {
+    let now = SystemTime::now();
+    let ago = |seconds: u64| format_time(now - Duration::from_secs(seconds));
+    let entry = |name: &str, kind: FileKind, size: Option<u64>, age: u64, git: GitStatus| EntryRow {
+        icon: icon_for(name, kind),
+        name: name.to_string(),
+        kind,
+        target: None,
+        size: size.map_or_else(|| "-".to_string(), format_size),
+        modified: ago(age),
+        permissions: match kind {
+            FileKind::Directory | FileKind::Executable => "rwxr-xr-x",
+            _ => "rw-r--r--",
+        }
+        .to_string(),
+        octal: match kind {
+            FileKind::Directory | FileKind::Executable => "0755",
+            _ => "0644",
+        }
+        .to_string(),
+        details: Details::default(),
+        git: Some(git),
+        error: None,
+    };
+    let rows = [
+        entry("docs", FileKind::Directory, None, 3 * 86400, GitStatus::Clean),
+        entry("src", FileKind::Directory, None, 2 * 3600, GitStatus::Modified),
+        entry("Cargo.toml", FileKind::File, Some(1260), 300, GitStatus::Staged),
+        entry("README.md", FileKind::File, Some(4915), 7 * 86400, GitStatus::Untracked),
+        entry("run", FileKind::Executable, Some(212), 3600, GitStatus::Clean),
+    ];
+
+    let node = |name: &str, git: GitStatus, children: Vec<TreeNode>| TreeNode {
+        path: PathBuf::from(name),
+        name: name.to_string(),
+        kind: if children.is_empty() {
+            FileKind::File
+        } else {
+            FileKind::Directory
+        },
+        size: None,
+        total: None,
+        truncated: false,
+        files: 0,
+        subdirs: 0,
+        collapsed: None,
+        modified: None,
+        target: None,
+        git: Some(git),
+        children,
+        error: None,
+        repeated: false,
+    };
+    let tree = [
+        node(
+            "src",
+            GitStatus::Modified,
+            vec![
+                node("display.rs", GitStatus::Clean, Vec::new()),
+                node("main.rs", GitStatus::Modified, Vec::new()),
+            ],
+        ),
+        node("Cargo.toml", GitStatus::Staged, Vec::new()),
+        node("README.md", GitStatus::Untracked, Vec::new()),
+    ];
+
+    let mut lines = vec![
+        render_entry_table(theme, &rows, &DEFAULT_COLUMNS, true),
+        String::new(),
+        format!("{}{} project{}", fg(theme.dir_name), icon_for("project", FileKind::Directory), reset()),
+    ];
+    render_tree(&tree, "", theme, TreeAnnotations::default(), &mut lines);
+    lines.join("\n")
+}
+
+pub fn show_theme_gallery(themes: &[Theme], format: OutputFormat) {
+    if !format.is_table() {
+        let mut columns = vec!["name", "description"];
+        columns.extend(SLOT_NAMES);
+        let mut records = Records::new(&columns);
+        for theme in themes {
+            let mut row = vec![json!(theme.name), json!(theme.description)];
+            row.extend(theme.slots().map(|(_, color)| json!(hex_color(color))));
+            records.push(row);
+        }
+        print_records(&records, format);
+        return;
+    }
+
+    for theme in themes {
+        println!(
+            "{}{}{} - {}",
+            fg(theme.header),
+            theme.name,
+            reset(),
+            theme.description
+        );
+        println!("{}", render_theme_preview(theme));
+        println!();
+    }
+}
+
+/// Extra information after each name in the tree.
+#[derive(Clone, Copy, Default)]
+pub struct TreeAnnotations {
+    // File sizes, and directory sizes summed over their contents
+    pub sizes: bool,
+    // Files and subdirectories directly inside each directory
+    pub counts: bool,
+    // Entries shown per directory before the rest is summarised
+    pub max_entries: Option<usize>,
+}
+
+/// What `show_tree` reads and how it orders it.
+pub struct TreeOptions<'a> {
+    pub annotations: TreeAnnotations,
+    pub max_depth: Option<usize>,
+    // `--all` and `--almost-all` both show dotfiles, there is no `.` or `..`
+    pub hidden: HiddenFiles,
+    pub git_ignore: bool,
+    pub git: bool,
+    pub sort: &'a SortOptions,
+    // Applies to everything but directories, which stay so that matches
+    // keep their place in the tree
+    pub filter: &'a EntryFilter,
+    // `--match`: only matching files and the directories leading to them
+    pub patterns: Vec<GlobMatcher>,
+    // Drop directories left without entries
+    pub prune: bool,
+    pub dirs_only: bool,
+    // Directories shown as one line without their contents
+    pub collapse: Vec<CollapsedDir>,
+    pub traversal: Traversal,
+}
+
+/// A directory name the tree collapses, e.g. `target` next to a Cargo.toml.
+#[derive(Clone, Debug)]
+pub struct CollapsedDir {
+    pub name: String,
+    // Only collapsed when the parent directory also holds this file
+    pub beside: Option<String>,
+}
+
+impl CollapsedDir {
+    fn applies(&self, path: &Path) -> bool {
+        path.file_name().is_some_and(|name| name == self.name.as_str())
+            && self.beside.as_ref().is_none_or(|file| path.with_file_name(file).exists())
+    }
+}
+
+impl TreeOptions<'_> {
+    /// Whether `path` is wanted by `--match`. Patterns with a `/` are matched
+    /// against the path below `root`, the others against the name.
+    fn matches_pattern(&self, root: &Path, path: &Path) -> bool {
+        self.patterns.is_empty()
+            || self.patterns.iter().any(|pattern| {
+                if pattern.glob().glob().contains('/') {
+                    path.strip_prefix(root)
+                        .is_ok_and(|relative| pattern.is_match(relative))
+                } else {
+                    path.file_name().is_some_and(|name| pattern.is_match(name))
+                }
+            })
+    }
+}
+
+struct TreeNode {
+    path: PathBuf,
+    name: String,
+    kind: FileKind,
+    // Only known for readable entries that are not directories
+    size: Option<u64>,
+    // Size of everything below a directory, with `--sizes`
+    total: Option<DirSize>,
+    // Children left out because of `--depth`
+    truncated: bool,
+    // Entries directly inside a directory, counted before `--dirs-only`
+    files: usize,
+    subdirs: usize,
+    // Set for collapsed directories, to the number of entries inside
+    collapsed: Option<usize>,
+    modified: Option<SystemTime>,
+    target: Option<PathBuf>,
+    git: Option<GitStatus>,
+    children: Vec<TreeNode>,
+    // Why the entry or a directory's children could not be read
+    error: Option<String>,
+    // A directory already reached through another followed symlink, which
+    // the sizes around it do not count again
+    repeated: bool,
+}
+
+/// Identity of a directory for finding symlink loops: device and inode on
+/// Unix, the canonical path elsewhere.
+#[cfg(unix)]
+type DirId = (u64, u64);
+#[cfg(not(unix))]
+type DirId = PathBuf;
+
+/// Directories met so far while walking one tree.
+#[derive(Default)]
+struct Visited {
+    // From the root down to the directory being read, so that a followed
+    // symlink leading back into them ends the walk there
+    ancestors: Vec<DirId>,
+    // Every directory walked into, so that one reached again through
+    // another followed symlink is not counted twice
+    counted: HashSet<DirId>,
+}
+
+impl Visited {
+    /// Starting at `root`, or nowhere if it cannot be identified.
+    fn new(root: Option<DirId>) -> Self {
+        Visited {
+            ancestors: root.iter().cloned().collect(),
+            counted: root.into_iter().collect(),
+        }
+    }
+}
+
+/// Identity of the directory `path` resolves to.
+fn dir_id(path: &Path) -> Option<DirId> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        fs::metadata(path).ok().map(|metadata| (metadata.dev(), metadata.ino()))
+    }
+    #[cfg(not(unix))]
+    {
+        path.canonicalize().ok()
+    }
+}
+
+/// Whether two directories are on the same file system. Only known on Unix,
+/// elsewhere `--one-file-system` has no effect.
+fn same_file_system(a: &DirId, b: &DirId) -> bool {
+    #[cfg(unix)]
+    {
+        a.0 == b.0
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = (a, b);
+        true
+    }
 }
 
-pub fn show_tree(theme: &Theme, max_depth: Option<usize>, show_all: bool) {
-    let current_dir = env::current_dir().expect("Could not get current directory");
+/// What stays the same while collecting the tree below one root.
+#[derive(Clone, Copy)]
+struct TreeWalk<'a> {
+    root: &'a Path,
+    options: &'a TreeOptions<'a>,
+    git_repo: Option<&'a GitRepo>,
+    // With --git-ignore, everything below the root that git does not ignore
+    unignored: Option<&'a HashSet<PathBuf>>,
+    max_depth: Option<usize>,
+    // Set while summing up a directory the tree does not show the contents of
+    measuring: bool,
+}
 
-    let root_name = current_dir
-        .file_name()
-        .unwrap_or_default()
-        .to_string_lossy();
-
-    let colored_root = format!(
-        "\x1b[38;2;{};{};{}m{} {}\x1b[0m",
-        theme.dir_name.0,
-        theme.dir_name.1,
-        theme.dir_name.2,
-        get_file_icon(&current_dir),
-        root_name
-    );
-    println!("{}", colored_root);
+impl<'a> TreeWalk<'a> {
+    fn new(
+        root: &'a Path,
+        options: &'a TreeOptions<'a>,
+        git_repo: Option<&'a GitRepo>,
+        unignored: Option<&'a HashSet<PathBuf>>,
+    ) -> Self {
+        TreeWalk {
+            root,
+            options,
+            git_repo,
+            unignored,
+            max_depth: options.max_depth,
+            measuring: false,
+        }
+    }
 
-    display_tree_recursive(&current_dir, "", true, 0, max_depth, show_all, theme);
-}
+    /// The tree below the root.
+    fn collect(&self) -> io::Result<Vec<TreeNode>> {
+        collect_tree(self.root, 0, self, &mut Visited::new(dir_id(self.root)))
+    }
 
-pub fn show_path_table(theme: &Theme) {
-    let path_env = match env::var("PATH") {
-        Ok(path) => path,
-        Err(_) => {
-            println!("Error: Could not read PATH environment variable");
-            return;
+    /// The entries of `dir` the tree shows, before filters.
+    fn entry_paths(&self, dir: &Path) -> io::Result<Vec<PathBuf>> {
+        let options = self.options;
+        let mut paths = entry_paths(dir, Some(1), options.hidden, false, options.traversal)?;
+        if let Some(unignored) = self.unignored {
+            paths.retain(|path| unignored.contains(path));
         }
-    };
+        Ok(paths)
+    }
 
-    let path_dirs: Vec<&str> = path_env.split(':').collect();
+    /// Size of a directory cut off by --depth or collapsed, summed from
+    /// everything the tree would show below it, like a directory it shows.
+    fn measure(&self, dir: &Path, id: Option<DirId>, visited: &mut Visited) -> DirSize {
+        let walk = TreeWalk {
+            git_repo: None,
+            max_depth: None,
+            measuring: true,
+            ..*self
+        };
+        let pushed = id.is_some();
+        visited.ancestors.extend(id);
+        let result = collect_tree(dir, 0, &walk, visited);
+        if pushed {
+            visited.ancestors.pop();
+        }
+        match result {
+            Ok(children) => subtree_size(&children, was_interrupted()),
+            Err(_) => DirSize {
+                bytes: 0,
+                partial: true,
+            },
+        }
+    }
+}
 
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_BORDERS_ONLY)
-        .apply_modifier(UTF8_ROUND_CORNERS)
-        .set_content_arrangement(ContentArrangement::Dynamic)
-        .set_header(vec![
-            Cell::new("#")
-                .add_attribute(Attribute::Bold)
-                .fg(Color::Rgb {
-                    r: theme.header.0,
-                    g: theme.header.1,
-                    b: theme.header.2,
-                }),
-            Cell::new("Directory")
-                .add_attribute(Attribute::Bold)
-                .fg(Color::Rgb {
-                    r: theme.header.0,
-                    g: theme.header.1,
-                    b: theme.header.2,
-                }),
-            Cell::new("Status")
-                .add_attribute(Attribute::Bold)
-                .fg(Color::Rgb {
-                    r: theme.header.0,
-                    g: theme.header.1,
-                    b: theme.header.2,
-                }),
-        ]);
+/// The paths below `root` that git does not ignore. Walking the tree once
+/// reads each .gitignore once, instead of once for every directory below it.
+/// With --sizes that includes what the tree measures but does not show, and
+/// otherwise one level more than it shows, to count collapsed directories.
+fn unignored_paths(root: &Path, options: &TreeOptions) -> HashSet<PathBuf> {
+    let sizes = options.annotations.sizes;
+    let collapse = if sizes { Vec::new() } else { options.collapse.clone() };
+    WalkBuilder::new(root)
+        .standard_filters(false)
+        .hidden(!options.hidden.shows_dotfiles())
+        .parents(true)
+        .git_ignore(true)
+        .git_exclude(true)
+        .git_global(true)
+        .max_depth(if sizes { None } else { options.max_depth.map(|max| max + 1) })
+        .follow_links(options.traversal.follow)
+        .same_file_system(options.traversal.one_file_system)
+        // The tree only counts the entries of collapsed directories
+        .filter_entry(move |entry| {
+            entry.depth() < 3
+                || !entry
+                    .path()
+                    .parent()
+                    .and_then(Path::parent)
+                    .is_some_and(|dir| collapse.iter().any(|collapsed| collapsed.applies(dir)))
+        })
+        .build()
+        // Errors are reported when the tree reads the same entries, but a
+        // symlink loop still names an entry the tree shows
+        .filter_map(|result| match result {
+            Ok(entry) => Some(entry.into_path()),
+            Err(err) => error_path(&err).map(Path::to_path_buf),
+        })
+        .collect()
+}
 
-    for (index, path_dir) in path_dirs.iter().enumerate() {
-        let path = std::path::Path::new(path_dir);
-        let exists = path.exists();
-        let is_dir = path.is_dir();
-        let is_symlink = path.is_symlink();
+/// The path a walk error is about, if it names one.
+fn error_path(err: &ignore::Error) -> Option<&Path> {
+    match err {
+        ignore::Error::Loop { child, .. } => Some(child),
+        ignore::Error::WithPath { path, .. } => Some(path),
+        ignore::Error::WithDepth { err, .. } | ignore::Error::WithLineNumber { err, .. } => error_path(err),
+        _ => None,
+    }
+}
 
-        let status = if !exists {
-            if is_symlink {
-                "Broken symlink"
-            } else {
-                "Missing"
-            }
-        } else if is_symlink && is_dir {
-            "Symlink to directory"
-        } else if is_symlink {
-            "Symlink to file"
-        } else if !is_dir {
-            "Not a directory"
-        } else {
-            "OK"
-        };
+/// Reads `dir` and everything below it. Only an unreadable `dir` is an error;
+/// unreadable subdirectories are kept as nodes carrying the error.
+fn collect_tree(
+    dir: &Path,
+    current_depth: usize,
+    walk: &TreeWalk,
+    visited: &mut Visited,
+) -> io::Result<Vec<TreeNode>> {
+    let (root, options, git_repo) = (walk.root, walk.options, walk.git_repo);
+    if let Some(max) = walk.max_depth
+        && current_depth >= max
+    {
+        return Ok(Vec::new());
+    }
+    if walk.measuring && was_interrupted() {
+        return Ok(Vec::new());
+    }
 
-        let dir_cell = if exists && is_dir {
-            let icon = if is_symlink {
-                "↪" 
+    let paths = walk.entry_paths(dir)?;
+
+    let mut nodes: Vec<TreeNode> = paths
+        .into_iter()
+        .filter_map(|path| {
+            let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+            let (kind, metadata, walk_into) = match fs::symlink_metadata(&path) {
+                Ok(metadata) => {
+                    let kind = FileKind::from_metadata(&path, &metadata);
+                    let info = EntryInfo::new(&name, kind, &metadata, Some(metadata.len()));
+                    let wanted = options.filter.matches(&info) && options.matches_pattern(root, &path);
+                    // Symlinked directories are leaves unless followed
+                    let walk_into = kind.is_dir() && (options.traversal.follow || !kind.is_symlink());
+                    if !walk_into && !wanted {
+                        return None;
+                    }
+                    (kind, metadata, walk_into)
+                }
+                Err(e) => {
+                    if !options.filter.matches(&EntryInfo::unreadable(&name))
+                        || !options.matches_pattern(root, &path)
+                    {
+                        return None;
+                    }
+                    if !walk.measuring {
+                        report_minor_problem();
+                    }
+                    return Some(TreeNode {
+                        name,
+                        git: git_repo.map(|repo| repo.status(&path)),
+                        target: None,
+                        path,
+                        kind: FileKind::File,
+                        size: None,
+                        total: None,
+                        truncated: false,
+                        files: 0,
+                        subdirs: 0,
+                        collapsed: None,
+                        modified: None,
+                        children: Vec::new(),
+                        error: Some(describe_io_error(&e)),
+                        repeated: false,
+                    });
+                }
+            };
+            let mut collapse_error = None;
+            let collapsed = (walk_into
+                && !walk.measuring
+                && options.collapse.iter().any(|collapsed| collapsed.applies(&path)))
+            .then(|| match walk.entry_paths(&path) {
+                Ok(paths) => paths.len(),
+                Err(e) => {
+                    report_minor_problem();
+                    collapse_error = Some(describe_io_error(&e));
+                    0
+                }
+            });
+            let id = walk_into.then(|| dir_id(&path)).flatten();
+            let looped = id.as_ref().is_some_and(|id| visited.ancestors.contains(id));
+            let other_file_system = options.traversal.one_file_system
+                && id
+                    .as_ref()
+                    .zip(visited.ancestors.first())
+                    .is_some_and(|(id, root_id)| !same_file_system(id, root_id));
+            let repeated = !looped
+                && !other_file_system
+                && id.iter().cloned().any(|id| !visited.counted.insert(id));
+            // A repeated directory is still shown and sized in full, from a
+            // fresh start so that nothing below it is taken as repeated too
+            let mut fresh = repeated.then(|| Visited {
+                ancestors: visited.ancestors.clone(),
+                counted: id.iter().cloned().collect(),
+            });
+            let visited = fresh.as_mut().unwrap_or(&mut *visited);
+            let (mut children, error) = if looped {
+                (Vec::new(), Some("symlink loop".to_string()))
+            } else if walk_into && collapsed.is_none() && !other_file_system {
+                visited.ancestors.extend(id.iter().cloned());
+                let result = collect_tree(&path, current_depth + 1, walk, visited);
+                if id.is_some() {
+                    visited.ancestors.pop();
+                }
+                match result {
+                    Ok(children) => (children, None),
+                    Err(e) => {
+                        if !walk.measuring {
+                            report_minor_problem();
+                        }
+                        (Vec::new(), Some(describe_io_error(&e)))
+                    }
+                }
             } else {
-                get_file_icon(path)
+                (Vec::new(), collapse_error)
             };
-            Cell::new(format!("{} {}", icon, path_dir)).fg(Color::Rgb {
-                r: theme.dir_name.0,
-                g: theme.dir_name.1,
-                b: theme.dir_name.2,
-            })
-        } else if is_symlink {
-            Cell::new(format!("↪ {}", path_dir)).fg(Color::Rgb {
-                r: theme.permissions.0,
-                g: theme.permissions.1,
-                b: theme.permissions.2,
-            })
-        } else {
-            Cell::new(format!("✗ {}", path_dir)).fg(Color::Rgb {
-                r: theme.file_name.0,
-                g: theme.file_name.1,
-                b: theme.file_name.2,
+            let truncated = walk_into
+                && (collapsed.is_some()
+                    || other_file_system
+                    || walk.max_depth.is_some_and(|max| current_depth + 1 >= max));
+            // With --match a directory has to lead to a match or match
+            // itself, and --prune drops empty ones. Unread (--depth,
+            // collapsed) or unreadable ones are only empty as far as we know,
+            // so both keep them.
+            if walk_into && children.is_empty() && !truncated && error.is_none() {
+                let unmatched = !options.patterns.is_empty() && !options.matches_pattern(root, &path);
+                if unmatched || options.prune {
+                    return None;
+                }
+            }
+            // Other file systems are not measured, their size stays partial
+            let total = (options.annotations.sizes && walk_into && !other_file_system).then(|| {
+                if truncated && !looped {
+                    walk.measure(&path, id, visited)
+                } else {
+                    // A loop repeats what is already counted, nothing is missing
+                    subtree_size(&children, error.is_some() && !looped)
+                }
+            });
+            let subdirs = children.iter().filter(|child| child.kind.is_dir()).count();
+            let files = children.len() - subdirs;
+            if options.dirs_only {
+                children.retain(|child| child.kind.is_dir());
+            }
+            Some(TreeNode {
+                name,
+                git: git_repo.map(|repo| repo.status(&path)),
+                target: kind.is_symlink().then(|| fs::read_link(&path).ok()).flatten(),
+                path,
+                kind,
+                size: (!walk_into).then_some(metadata.len()),
+                total,
+                truncated,
+                files,
+                subdirs,
+                collapsed,
+                modified: metadata.modified().ok(),
+                children,
+                error,
+                repeated,
             })
-        };
+        })
+        .collect();
+    options.sort.sort(&mut nodes, &DEFAULT_TREE_ORDER, TreeNode::info);
+    Ok(nodes)
+}
 
-        let status_cell = match status {
-            "OK" => Cell::new(status).fg(Color::Rgb {
-                r: theme.file_size.0,
-                g: theme.file_size.1,
-                b: theme.file_size.2,
-            }),
-            _ => Cell::new(status).fg(Color::Rgb {
-                r: theme.modified.0,
-                g: theme.modified.1,
-                b: theme.modified.2,
-            }),
-        };
+impl TreeNode {
+    fn info(&self) -> EntryInfo<'_> {
+        EntryInfo {
+            name: &self.name,
+            kind: self.error.is_none().then_some(self.kind),
+            size: self.size,
+            modified: self.modified,
+            mode: None,
+        }
+    }
+}
 
-        table.add_row(vec![
-            Cell::new((index + 1).to_string()).fg(Color::Rgb {
-                r: theme.row_number.0,
-                g: theme.row_number.1,
-                b: theme.row_number.2,
-            }),
-            dir_cell,
-            status_cell,
-        ]);
+/// Size of a directory from its collected children. Partial when the
+/// directory or anything below it could not be read.
+fn subtree_size(children: &[TreeNode], unreadable: bool) -> DirSize {
+    children.iter().map(node_size).fold(
+        DirSize {
+            bytes: 0,
+            partial: unreadable,
+        },
+        |total, size| DirSize {
+            bytes: total.bytes + size.bytes,
+            partial: total.partial || size.partial,
+        },
+    )
+}
+
+/// Size of an entry and everything collected below it. Directories that
+/// were not read count as partial unless `--sizes` measured them. Symlinks
+/// that were not followed count with their own size, and directories
+/// reached before through another one not at all.
+fn node_size(node: &TreeNode) -> DirSize {
+    if node.repeated {
+        return DirSize::default();
+    }
+    match (node.total, node.size) {
+        (Some(total), _) => total,
+        (None, Some(size)) => DirSize {
+            bytes: size,
+            partial: false,
+        },
+        (None, None) if node.kind.is_dir() => {
+            subtree_size(&node.children, node.truncated || node.error.is_some())
+        }
+        (None, None) => DirSize {
+            bytes: 0,
+            partial: node.error.is_some(),
+        },
     }
+}
 
-    let table_output = table.to_string();
-    let colored_output = colorize_borders(&table_output, theme);
-    println!("{colored_output}");
+/// Directories, files and bytes shown in the tree, for the footer.
+#[derive(Default)]
+struct TreeSummary {
+    directories: usize,
+    files: usize,
+    size: DirSize,
 }
 
-fn display_tree_recursive(
-    dir: &std::path::Path,
-    prefix: &str,
-    _is_last: bool,
-    current_depth: usize,
-    max_depth: Option<usize>,
-    show_all: bool,
-    theme: &Theme,
-) {
-    if let Some(max) = max_depth {
-        if current_depth >= max {
-            return;
+impl TreeSummary {
+    /// Adds the tree below one root.
+    fn add(&mut self, tree: &RootTree) {
+        self.size = DirSize {
+            bytes: self.size.bytes + tree.size.bytes,
+            partial: self.size.partial || tree.size.partial,
+        };
+        self.files += tree.files;
+        self.count_directories(&tree.nodes);
+    }
+
+    // Files are taken from the counts, as `--dirs-only` drops them
+    fn count_directories(&mut self, nodes: &[TreeNode]) {
+        for node in nodes.iter().filter(|node| node.kind.is_dir()) {
+            self.directories += 1;
+            self.files += node.files;
+            self.count_directories(&node.children);
         }
     }
 
-    let entries = match fs::read_dir(dir) {
-        Ok(entries) => entries,
-        Err(_) => return,
-    };
+    fn render(&self, theme: &Theme, sizes: bool) -> String {
+        let mut line = format!(
+            "{}, {}",
+            plural(self.directories, "directory", "directories"),
+            plural(self.files, "file", "files")
+        );
+        if sizes {
+            line.push_str(&format!(", {}", format_dir_size(self.size)));
+        }
+        format!("{}{}{}", fg(theme.dir_size), line, reset())
+    }
+}
+
+fn plural(count: usize, one: &str, many: &str) -> String {
+    format!("{count} {}", if count == 1 { one } else { many })
+}
 
-    let mut items: Vec<_> = entries.filter_map(|entry| entry.ok()).collect();
-    items.sort_by(|a, b| {
-        let a_is_dir = a.path().is_dir();
-        let b_is_dir = b.path().is_dir();
+fn format_dir_size(size: DirSize) -> String {
+    if size.partial {
+        format!("≥ {}", format_size(size.bytes))
+    } else {
+        format_size(size.bytes)
+    }
+}
 
-        match (a_is_dir, b_is_dir) {
-            (true, false) => std::cmp::Ordering::Less,
-            (false, true) => std::cmp::Ordering::Greater,
-            _ => a.file_name().cmp(&b.file_name()),
+fn tree_records(nodes: &[TreeNode], depth: usize, records: &mut Records) {
+    for node in nodes {
+        let mut row = vec![json!(depth)];
+        match fs::symlink_metadata(&node.path) {
+            Ok(metadata) => {
+                let size = node.size.or(node.total.map(|total| total.bytes));
+                row.extend(entry_record(
+                    &node.path,
+                    &node.name,
+                    &metadata,
+                    size,
+                    node.total.is_some_and(|total| total.partial),
+                    node.target.as_deref(),
+                    node.git,
+                ));
+                row.push(json!(node.error));
+            }
+            Err(e) => {
+                report_minor_problem();
+                row.extend(error_record(&node.path, &node.name, &describe_io_error(&e)));
+            }
         }
-    });
+        records.push(row);
+        tree_records(&node.children, depth + 1, records);
+    }
+}
 
-    if !show_all {
-        items.retain(|item| !item.file_name().to_string_lossy().starts_with('.'));
+/// `(12.3 KB, 5 files, 2 dirs)` after a directory, `(3.1 KB)` after a file.
+fn tree_annotation(node: &TreeNode, annotations: TreeAnnotations, theme: &Theme) -> String {
+    let mut parts = Vec::new();
+    if annotations.sizes {
+        if let Some(total) = node.total {
+            parts.push(format_dir_size(total));
+        } else if let Some(size) = node.size {
+            parts.push(format_size(size));
+        }
+    }
+    if let Some(entries) = node.collapsed {
+        parts.insert(0, "collapsed".to_string());
+        if node.error.is_none() {
+            parts.push(plural(entries, "entry", "entries"));
+        }
+    }
+    // Symlinks that were not followed have a size of their own and no entries
+    let walked = node.kind.is_dir() && node.size.is_none();
+    if annotations.counts && walked && !node.truncated && node.error.is_none() {
+        parts.push(plural(node.files, "file", "files"));
+        parts.push(plural(node.subdirs, "dir", "dirs"));
     }
+    if parts.is_empty() {
+        return String::new();
+    }
+    let color = if node.kind.is_dir() { theme.dir_size } else { theme.file_size };
+    format!(" {}({}){}", fg(color), parts.join(", "), reset())
+}
 
-    let total_items = items.len();
+/// Renders the tree lines below the root, one string per entry.
+fn render_tree(
+    nodes: &[TreeNode],
+    prefix: &str,
+    theme: &Theme,
+    annotations: TreeAnnotations,
+    lines: &mut Vec<String>,
+) {
+    let shown = annotations
+        .max_entries
+        .map_or(nodes.len(), |max| max.min(nodes.len()));
+    let (nodes, omitted) = nodes.split_at(shown);
+    let total_items = nodes.len() + usize::from(!omitted.is_empty());
 
-    for (index, entry) in items.iter().enumerate() {
-        let path = entry.path();
+    for (index, node) in nodes.iter().enumerate() {
         let is_last_item = index == total_items - 1;
 
-        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+        let file_name = &node.name;
 
         let current_prefix = if is_last_item {
             "└── "
@@ -689,96 +2258,140 @@ fn display_tree_recursive(
             "├── "
         };
 
-        let icon = get_file_icon(&path);
-        let (name_color, type_indicator) = if path.is_dir() {
+        let icon = icon_for(file_name, node.kind);
+        let (name_color, type_indicator) = if node.kind == FileKind::BrokenSymlink {
+            (fg(theme.broken_link_color()), "")
+        } else if node.kind.is_symlink() {
+            (fg(theme.symlink_color()), if node.kind.is_dir() { "/" } else { "" })
+        } else if node.kind.is_dir() {
             (
-                format!(
-                    "\x1b[38;2;{};{};{}m",
-                    theme.dir_name.0, theme.dir_name.1, theme.dir_name.2
-                ),
+                fg(theme.dir_name),
                 "/",
             )
         } else {
             (
-                format!(
-                    "\x1b[38;2;{};{};{}m",
-                    theme.file_name.0, theme.file_name.1, theme.file_name.2
-                ),
+                fg(theme.file_name),
                 "",
             )
         };
 
-        let tree_color = format!(
-            "\x1b[38;2;{};{};{}m",
-            theme.border.0, theme.border.1, theme.border.2
-        );
-        let reset_color = "\x1b[0m";
+        let tree_color = fg(theme.border);
+        let reset_color = reset();
 
-        println!(
-            "{}{}{}{}{}{}{}{}{}",
+        let link_target = match &node.target {
+            Some(target) => format!(" {}→ {}{}", name_color, target.display(), reset_color),
+            None => String::new(),
+        };
+
+        let git_marker = match node.git {
+            Some(status) if status != GitStatus::Clean => {
+                format!(" {}[{}]{}", fg(status.color(theme)), status.marker(), reset_color)
+            }
+            _ => String::new(),
+        };
+
+        let error = match &node.error {
+            Some(error) => format!(" {}[{}]{}", fg(theme.broken_link_color()), error, reset_color),
+            None => String::new(),
+        };
+
+        lines.push(format!(
+            "{}{}{}{}{}{} {}{}{}{}{}{}",
             prefix,
             tree_color,
             current_prefix,
             reset_color,
             name_color,
             icon,
-            " ",
             file_name,
-            if path.is_dir() {
-                format!(
-                    "{}{}{}",
-                    format!(
-                        "\x1b[38;2;{};{};{}m",
-                        theme.dir_type.0, theme.dir_type.1, theme.dir_type.2
-                    ),
-                    type_indicator,
-                    reset_color
-                )
+            if node.kind.is_dir() {
+                format!("{}{}{}", fg(theme.dir_type), type_indicator, reset_color)
             } else {
                 reset_color.to_string()
-            }
-        );
-
-        if path.is_dir() {
+            },
+            link_target,
+            tree_annotation(node, annotations, theme),
+            git_marker,
+            error
+        ));
+
+        if node.kind.is_dir() {
             let colored_next_prefix = if is_last_item {
                 "    "
             } else {
                 &format!("{}│{}{:3}", tree_color, reset_color, "")
             };
             let new_prefix = format!("{}{}", prefix, colored_next_prefix);
-            display_tree_recursive(
-                &path,
-                &new_prefix,
-                is_last_item,
-                current_depth + 1,
-                max_depth,
-                show_all,
-                theme,
-            );
+            render_tree(&node.children, &new_prefix, theme, annotations, lines);
         }
     }
+
+    if !omitted.is_empty() {
+        lines.push(format!(
+            "{}{}└── {}{}… {} more {} ({}){}",
+            prefix,
+            fg(theme.border),
+            reset(),
+            fg(theme.dir_size),
+            omitted.len(),
+            if omitted.len() == 1 { "entry" } else { "entries" },
+            format_dir_size(subtree_size(omitted, false)),
+            reset()
+        ));
+    }
 }
 
-pub fn show_structured_data(theme: &Theme, file_path: &str) {
-    match parse_file(file_path) {
-        Ok(parsed_data) => {
-            let title_color = format!(
-                "\x1b[38;2;{};{};{}m",
-                theme.header.0, theme.header.1, theme.header.2
-            );
-            let reset_color = "\x1b[0m";
-
-            println!(
-                "{}Structured Data ({}) - {}{}", 
-                title_color, parsed_data.format, file_path, reset_color
-            );
-            println!();
+pub fn show_structured_data(
+    theme: &Theme,
+    file_path: &str,
+    format: OutputFormat,
+) -> Result<(), LsaError> {
+    let parsed_data = parse_file(file_path)?;
+    if !format.is_table() {
+        let mut records = Records::new(&["key", "type", "value"]);
+        flatten_data_records("", &parsed_data.data, &mut records);
+        print_records(&records, format);
+        return Ok(());
+    }
+
+    let title_color = fg(theme.header);
+    let reset_color = reset();
+
+    println!(
+        "{}Structured Data ({}) - {}{}", 
+        title_color, parsed_data.format, file_path, reset_color
+    );
+    println!();
+
+    render_flattened_data(&parsed_data.data, theme);
+    Ok(())
+}
 
-            render_flattened_data(&parsed_data.data, theme);
+/// Flattens nested data into one record per leaf, keyed by its path
+/// (`servers[0].host`). Object keys are sorted so the output is stable.
+fn flatten_data_records(key: &str, data: &DataValue, records: &mut Records) {
+    match data {
+        DataValue::Object(obj) => {
+            let mut keys: Vec<_> = obj.keys().collect();
+            keys.sort();
+            for child_key in keys {
+                let child_path = if key.is_empty() {
+                    child_key.clone()
+                } else {
+                    format!("{key}.{child_key}")
+                };
+                flatten_data_records(&child_path, &obj[child_key], records);
+            }
         }
-        Err(e) => {
-            eprintln!("Error parsing file '{}': {}", file_path, e);
+        DataValue::Array(arr) => {
+            for (i, value) in arr.iter().enumerate() {
+                flatten_data_records(&format!("{key}[{i}]"), value, records);
+            }
         }
+        DataValue::String(s) => records.push(vec![json!(key), json!("string"), json!(s)]),
+        DataValue::Number(n) => records.push(vec![json!(key), json!("number"), json!(n)]),
+        DataValue::Boolean(b) => records.push(vec![json!(key), json!("boolean"), json!(b)]),
+        DataValue::Null => records.push(vec![json!(key), json!("null"), Value::Null]),
     }
 }
 
@@ -789,7 +2402,7 @@ fn render_flattened_data(data: &DataValue, theme: &Theme) {
 fn render_main_table_with_nested(data: &DataValue, theme: &Theme) {
     match data {
         DataValue::Object(obj) => {
-            let mut table = Table::new();
+            let mut table = new_table();
             table
                 .load_preset(UTF8_BORDERS_ONLY)
                 .apply_modifier(UTF8_ROUND_CORNERS)
@@ -797,33 +2410,17 @@ fn render_main_table_with_nested(data: &DataValue, theme: &Theme) {
                 .set_header(vec![
                     Cell::new("Key")
                         .add_attribute(Attribute::Bold)
-                        .fg(Color::Rgb {
-                            r: theme.header.0,
-                            g: theme.header.1,
-                            b: theme.header.2,
-                        }),
+                        .fg(cell_color(theme.header)),
                     Cell::new("Value")
                         .add_attribute(Attribute::Bold)
-                        .fg(Color::Rgb {
-                            r: theme.header.0,
-                            g: theme.header.1,
-                            b: theme.header.2,
-                        }),
+                        .fg(cell_color(theme.header)),
                 ]);
 
             for (i, (key, value)) in obj.iter().enumerate() {
                 let key_color = if i % 2 == 0 {
-                    Color::Rgb {
-                        r: theme.file_name.0,
-                        g: theme.file_name.1,
-                        b: theme.file_name.2,
-                    }
+                    cell_color(theme.file_name)
                 } else {
-                    Color::Rgb {
-                        r: theme.dir_name.0,
-                        g: theme.dir_name.1,
-                        b: theme.dir_name.2,
-                    }
+                    cell_color(theme.dir_name)
                 };
 
                 let value_cell_content = if value.is_simple_value() {
@@ -835,25 +2432,13 @@ fn render_main_table_with_nested(data: &DataValue, theme: &Theme) {
 
                 let value_color = if value.is_simple_value() {
                     if i % 2 == 0 {
-                        Color::Rgb {
-                            r: theme.file_size.0,
-                            g: theme.file_size.1,
-                            b: theme.file_size.2,
-                        }
+                        cell_color(theme.file_size)
                     } else {
-                        Color::Rgb {
-                            r: theme.modified.0,
-                            g: theme.modified.1,
-                            b: theme.modified.2,
-                        }
+                        cell_color(theme.modified)
                     }
                 } else {
                     // For nested tables, use a neutral color
-                    Color::Rgb {
-                        r: theme.permissions.0,
-                        g: theme.permissions.1,
-                        b: theme.permissions.2,
-                    }
+                    cell_color(theme.permissions)
                 };
 
                 table.add_row(vec![
@@ -867,7 +2452,7 @@ fn render_main_table_with_nested(data: &DataValue, theme: &Theme) {
             println!("{}", colored_output);
         }
         DataValue::Array(arr) => {
-            let mut table = Table::new();
+            let mut table = new_table();
             table
                 .load_preset(UTF8_BORDERS_ONLY)
                 .apply_modifier(UTF8_ROUND_CORNERS)
@@ -875,33 +2460,17 @@ fn render_main_table_with_nested(data: &DataValue, theme: &Theme) {
                 .set_header(vec![
                     Cell::new("Index")
                         .add_attribute(Attribute::Bold)
-                        .fg(Color::Rgb {
-                            r: theme.header.0,
-                            g: theme.header.1,
-                            b: theme.header.2,
-                        }),
+                        .fg(cell_color(theme.header)),
                     Cell::new("Value")
                         .add_attribute(Attribute::Bold)
-                        .fg(Color::Rgb {
-                            r: theme.header.0,
-                            g: theme.header.1,
-                            b: theme.header.2,
-                        }),
+                        .fg(cell_color(theme.header)),
                 ]);
 
             for (i, value) in arr.iter().enumerate() {
                 let index_color = if i % 2 == 0 {
-                    Color::Rgb {
-                        r: theme.row_number.0,
-                        g: theme.row_number.1,
-                        b: theme.row_number.2,
-                    }
+                    cell_color(theme.row_number)
                 } else {
-                    Color::Rgb {
-                        r: theme.file_type.0,
-                        g: theme.file_type.1,
-                        b: theme.file_type.2,
-                    }
+                    cell_color(theme.file_type)
                 };
 
                 let value_cell_content = if value.is_simple_value() {
@@ -912,24 +2481,12 @@ fn render_main_table_with_nested(data: &DataValue, theme: &Theme) {
 
                 let value_color = if value.is_simple_value() {
                     if i % 2 == 0 {
-                        Color::Rgb {
-                            r: theme.file_size.0,
-                            g: theme.file_size.1,
-                            b: theme.file_size.2,
-                        }
+                        cell_color(theme.file_size)
                     } else {
-                        Color::Rgb {
-                            r: theme.modified.0,
-                            g: theme.modified.1,
-                            b: theme.modified.2,
-                        }
+                        cell_color(theme.modified)
                     }
                 } else {
-                    Color::Rgb {
-                        r: theme.permissions.0,
-                        g: theme.permissions.1,
-                        b: theme.permissions.2,
-                    }
+                    cell_color(theme.permissions)
                 };
 
                 table.add_row(vec![
@@ -944,7 +2501,7 @@ fn render_main_table_with_nested(data: &DataValue, theme: &Theme) {
         }
         _ => {
             // For simple values, just display them in a single-column table
-            let mut table = Table::new();
+            let mut table = new_table();
             table
                 .load_preset(UTF8_BORDERS_ONLY)
                 .apply_modifier(UTF8_ROUND_CORNERS)
@@ -952,19 +2509,11 @@ fn render_main_table_with_nested(data: &DataValue, theme: &Theme) {
                 .set_header(vec![
                     Cell::new("Value")
                         .add_attribute(Attribute::Bold)
-                        .fg(Color::Rgb {
-                            r: theme.header.0,
-                            g: theme.header.1,
-                            b: theme.header.2,
-                        }),
+                        .fg(cell_color(theme.header)),
                 ]);
 
             table.add_row(vec![
-                Cell::new(data.to_display_string()).fg(Color::Rgb {
-                    r: theme.file_name.0,
-                    g: theme.file_name.1,
-                    b: theme.file_name.2,
-                }),
+                Cell::new(data.to_display_string()).fg(cell_color(theme.file_name)),
             ]);
 
             let table_output = table.to_string();
@@ -1034,7 +2583,7 @@ fn strip_ansi_codes(input: &str) -> String {
     while let Some(ch) = chars.next() {
         if ch == '\x1b' {
             // Skip ANSI escape sequences
-            while let Some(next_ch) = chars.next() {
+            for next_ch in chars.by_ref() {
                 if next_ch.is_ascii_alphabetic() {
                     break;
                 }
@@ -1050,3 +2599,493 @@ fn strip_ansi_codes(input: &str) -> String {
 
 
 
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        CollapsedDir, EntryRow, ListOptions, ListedEntry, Traversal, TreeAnnotations, TreeOptions, TreeSummary,
+        TreeWalk, Visited, collect_tree, dir_id, read_directory, read_entry, read_root, render_entry_table,
+        render_tree, strip_ansi_codes, tree_lines, unignored_paths,
+    };
+    use crate::columns::DEFAULT_COLUMNS;
+    use crate::dirsize::DirSize;
+    use crate::error::LsaError;
+    use crate::filter::{EntryFilter, HiddenFiles, parse_glob};
+    use crate::sort::{SortBy, SortOptions};
+    use crate::themes::builtin_themes;
+
+    fn list_options<'a>(sort: &'a SortOptions, filter: &'a EntryFilter) -> ListOptions<'a> {
+        ListOptions {
+            sort,
+            dir_sizes: false,
+            dereference: false,
+            git: false,
+            filter,
+            columns: &DEFAULT_COLUMNS,
+            grid: None,
+            hidden: HiddenFiles::Hide,
+            recursive: false,
+            git_ignore: false,
+            limit: None,
+            traversal: Traversal::default(),
+        }
+    }
+
+    fn tree_options<'a>(sort: &'a SortOptions, filter: &'a EntryFilter) -> TreeOptions<'a> {
+        TreeOptions {
+            annotations: TreeAnnotations::default(),
+            max_depth: None,
+            hidden: HiddenFiles::Hide,
+            git_ignore: false,
+            git: false,
+            sort,
+            filter,
+            patterns: Vec::new(),
+            prune: false,
+            dirs_only: false,
+            collapse: Vec::new(),
+            traversal: Traversal::default(),
+        }
+    }
+
+    fn shown_names(entries: &[ListedEntry]) -> Vec<&str> {
+        entries.iter().map(ListedEntry::shown_name).collect()
+    }
+
+    #[test]
+    fn test_unreadable_entry_is_kept_with_error() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let entry = read_entry(dir.path().join("vanished"), false, false, Traversal::default());
+        assert_eq!(entry.name, "vanished");
+        assert!(entry.metadata.is_none());
+        assert_eq!(entry.error.as_deref(), Some("no such file or directory"));
+
+        let row = EntryRow {
+            icon: "",
+            name: entry.name,
+            kind: entry.kind,
+            target: None,
+            size: "?".to_string(),
+            modified: "?".to_string(),
+            permissions: "?".to_string(),
+            octal: "?".to_string(),
+            details: Default::default(),
+            git: None,
+            error: entry.error,
+        };
+        let table = render_entry_table(&builtin_themes()[0], &[row], &DEFAULT_COLUMNS, false);
+        assert!(table.contains("no such file or directory"), "{table}");
+    }
+
+    #[test]
+    fn test_recursive_listing_filters_by_file_name() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join("src/sub")).unwrap();
+        std::fs::write(dir.path().join("README.md"), vec![0u8; 100]).unwrap();
+        std::fs::write(dir.path().join("src/main.rs"), vec![0u8; 30]).unwrap();
+        std::fs::write(dir.path().join("src/lib.rs"), vec![0u8; 10]).unwrap();
+        std::fs::write(dir.path().join("src/sub/main_test.rs"), vec![0u8; 20]).unwrap();
+        std::fs::write(dir.path().join("src/sub/.env"), "").unwrap();
+
+        let default_sort = SortOptions::default();
+        let by_size = SortOptions {
+            keys: vec![SortBy::Size],
+            reverse: true,
+            ..Default::default()
+        };
+        let names = EntryFilter {
+            names: vec![parse_glob("main*").unwrap()],
+            ..Default::default()
+        };
+        let recursive = |sort, filter| ListOptions {
+            recursive: true,
+            ..list_options(sort, filter)
+        };
+
+        let entries = read_directory(dir.path(), &recursive(&default_sort, &names)).unwrap();
+        assert_eq!(shown_names(&entries), ["src/main.rs", "src/sub/main_test.rs"]);
+
+        let largest = ListOptions {
+            limit: Some(1),
+            ..recursive(&by_size, &names)
+        };
+        let entries = read_directory(dir.path(), &largest).unwrap();
+        assert_eq!(shown_names(&entries), ["src/main.rs"]);
+
+        let query = EntryFilter {
+            query: Some("name = main.rs".parse().unwrap()),
+            ..Default::default()
+        };
+        let entries = read_directory(dir.path(), &recursive(&default_sort, &query)).unwrap();
+        assert_eq!(shown_names(&entries), ["src/main.rs"]);
+
+        let hidden = EntryFilter {
+            query: Some("hidden".parse().unwrap()),
+            ..Default::default()
+        };
+        let all = ListOptions {
+            hidden: HiddenFiles::AlmostAll,
+            ..recursive(&default_sort, &hidden)
+        };
+        let entries = read_directory(dir.path(), &all).unwrap();
+        assert_eq!(shown_names(&entries), ["src/sub/.env"]);
+    }
+
+    #[test]
+    fn test_hidden_entries_in_listing() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "").unwrap();
+        std::fs::write(dir.path().join(".env"), "").unwrap();
+
+        let (sort, filter) = (SortOptions::default(), EntryFilter::default());
+        let names = |hidden| {
+            let options = ListOptions {
+                hidden,
+                ..list_options(&sort, &filter)
+            };
+            let entries = read_directory(dir.path(), &options).unwrap();
+            shown_names(&entries).iter().map(|name| name.to_string()).collect::<Vec<_>>()
+        };
+        assert_eq!(names(HiddenFiles::All), [".", "..", ".env", "a.txt"]);
+        assert_eq!(names(HiddenFiles::AlmostAll), [".env", "a.txt"]);
+        assert_eq!(names(HiddenFiles::Hide), ["a.txt"]);
+    }
+
+    #[test]
+    fn test_dir_sizes_leave_out_parent_entry() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+        std::fs::write(dir.path().join("sub/a.txt"), vec![0u8; 100]).unwrap();
+        std::fs::write(dir.path().join("big.bin"), vec![0u8; 1000]).unwrap();
+
+        let (sort, filter) = (SortOptions::default(), EntryFilter::default());
+        let options = ListOptions {
+            hidden: HiddenFiles::All,
+            dir_sizes: true,
+            ..list_options(&sort, &filter)
+        };
+        let entries = read_directory(&dir.path().join("sub"), &options).unwrap();
+        let sizes: Vec<_> = entries
+            .iter()
+            .map(|entry| (entry.shown_name(), entry.dir_size.map(|size| size.bytes)))
+            .collect();
+        assert_eq!(sizes, [(".", Some(100)), ("..", None), ("a.txt", None)]);
+    }
+
+    #[test]
+    fn test_git_ignore_in_listing() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join(".git")).unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join(".gitignore"), "*.log\n").unwrap();
+        std::fs::write(dir.path().join("build.log"), "").unwrap();
+        std::fs::write(dir.path().join("a.txt"), "").unwrap();
+        std::fs::write(dir.path().join("src/debug.log"), "").unwrap();
+        std::fs::write(dir.path().join("src/main.rs"), "").unwrap();
+
+        let (sort, filter) = (SortOptions::default(), EntryFilter::default());
+        let ignoring = ListOptions {
+            git_ignore: true,
+            ..list_options(&sort, &filter)
+        };
+        let entries = read_directory(dir.path(), &ignoring).unwrap();
+        assert_eq!(shown_names(&entries), ["a.txt", "src"]);
+
+        let recursive = ListOptions {
+            recursive: true,
+            ..ignoring
+        };
+        let entries = read_directory(dir.path(), &recursive).unwrap();
+        assert_eq!(shown_names(&entries), ["a.txt", "src", "src/main.rs"]);
+
+        // The .gitignore in the parent applies when listing a subdirectory
+        let entries = read_directory(&dir.path().join("src"), &ignoring).unwrap();
+        assert_eq!(shown_names(&entries), ["main.rs"]);
+    }
+
+    #[test]
+    fn test_git_ignore_in_tree() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join(".git")).unwrap();
+        std::fs::create_dir_all(dir.path().join("src/gen")).unwrap();
+        std::fs::write(dir.path().join(".gitignore"), "*.log\n").unwrap();
+        std::fs::write(dir.path().join("src/.gitignore"), "gen/\n").unwrap();
+        std::fs::write(dir.path().join("build.log"), "").unwrap();
+        std::fs::write(dir.path().join("src/main.rs"), "").unwrap();
+        std::fs::write(dir.path().join("src/debug.log"), "").unwrap();
+        std::fs::write(dir.path().join("src/gen/out.rs"), "").unwrap();
+
+        let (sort, filter) = (SortOptions::default(), EntryFilter::default());
+        let options = TreeOptions {
+            git_ignore: true,
+            ..tree_options(&sort, &filter)
+        };
+        let unignored = unignored_paths(dir.path(), &options);
+        let nodes = TreeWalk::new(dir.path(), &options, None, Some(&unignored)).collect().unwrap();
+        let names: Vec<_> = nodes.iter().map(|node| node.name.as_str()).collect();
+        assert_eq!(names, ["src"]);
+        let src: Vec<_> = nodes[0].children.iter().map(|node| node.name.as_str()).collect();
+        assert_eq!(src, ["main.rs"]);
+    }
+
+    #[test]
+    fn test_bad_tree_roots() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let file = dir.path().join("file.txt");
+        std::fs::write(&file, "").unwrap();
+        let missing = dir.path().join("missing");
+        let empty = dir.path().join("empty");
+        std::fs::create_dir(&empty).unwrap();
+
+        let (sort, filter) = (SortOptions::default(), EntryFilter::default());
+        let options = tree_options(&sort, &filter);
+        assert!(matches!(read_root(&missing, &options), Err(LsaError::Io { .. })));
+        assert!(matches!(read_root(&file, &options), Err(LsaError::WrongKind { .. })));
+
+        // Bad roots are reported and skipped, the rest is still shown
+        let (trees, errors): (Vec<_>, Vec<_>) = [missing, file, empty.clone()]
+            .iter()
+            .map(|root| read_root(root, &options))
+            .partition(Result::is_ok);
+        assert_eq!(errors.len(), 2);
+        let trees: Vec<_> = trees.into_iter().map(Result::unwrap).collect();
+        let lines = tree_lines(&builtin_themes()[0], &trees, options.annotations);
+        assert_eq!(lines.len(), 1);
+        assert!(strip_ansi_codes(&lines[0]).ends_with(&empty.display().to_string()));
+    }
+
+    #[test]
+    fn test_tree_skeleton_keeps_unread_directories() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join("a/b/c")).unwrap();
+        std::fs::create_dir_all(dir.path().join("a/empty")).unwrap();
+        std::fs::create_dir_all(dir.path().join("x")).unwrap();
+        std::fs::write(dir.path().join("a/b/c/hit.rs"), "").unwrap();
+        std::fs::write(dir.path().join("x/readme.md"), "").unwrap();
+
+        fn skeleton(nodes: &[super::TreeNode], prefix: &str, paths: &mut Vec<String>) {
+            for node in nodes {
+                let path = format!("{prefix}{}", node.name);
+                skeleton(&node.children, &format!("{path}/"), paths);
+                paths.push(path);
+            }
+        }
+        let (sort, filter) = (SortOptions::default(), EntryFilter::default());
+        let shown = |options: TreeOptions| {
+            let nodes = TreeWalk::new(dir.path(), &options, None, None).collect().unwrap();
+            let mut paths = Vec::new();
+            skeleton(&nodes, "", &mut paths);
+            paths.sort();
+            paths
+        };
+        let cut_off = || TreeOptions {
+            max_depth: Some(3),
+            ..tree_options(&sort, &filter)
+        };
+
+        // a/b/c is not read at depth 3, so it may still hold a match
+        let matching = TreeOptions {
+            patterns: vec![parse_glob("*.rs").unwrap()],
+            ..cut_off()
+        };
+        assert_eq!(shown(matching), ["a", "a/b", "a/b/c"]);
+
+        let pruned = TreeOptions {
+            prune: true,
+            ..cut_off()
+        };
+        assert_eq!(shown(pruned), ["a", "a/b", "a/b/c", "x", "x/readme.md"]);
+
+        let dirs_only = TreeOptions {
+            dirs_only: true,
+            ..cut_off()
+        };
+        assert_eq!(shown(dirs_only), ["a", "a/b", "a/b/c", "a/empty", "x"]);
+
+        let full_match = TreeOptions {
+            patterns: vec![parse_glob("*.rs").unwrap()],
+            ..tree_options(&sort, &filter)
+        };
+        assert_eq!(shown(full_match), ["a", "a/b", "a/b/c", "a/b/c/hit.rs"]);
+    }
+
+    #[test]
+    fn test_collapsed_directories() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join("target/debug")).unwrap();
+        std::fs::create_dir_all(dir.path().join("docs/target")).unwrap();
+        std::fs::write(dir.path().join("Cargo.toml"), "").unwrap();
+        std::fs::write(dir.path().join("target/.rustc_info.json"), "").unwrap();
+        std::fs::write(dir.path().join("target/CACHEDIR.TAG"), "").unwrap();
+        std::fs::write(dir.path().join("docs/target/page.md"), "").unwrap();
+
+        let (sort, filter) = (SortOptions::default(), EntryFilter::default());
+        let options = TreeOptions {
+            collapse: vec![CollapsedDir {
+                name: "target".to_string(),
+                beside: Some("Cargo.toml".to_string()),
+            }],
+            ..tree_options(&sort, &filter)
+        };
+        let nodes = TreeWalk::new(dir.path(), &options, None, None).collect().unwrap();
+        let target = nodes.iter().find(|node| node.name == "target").unwrap();
+        // Dotfiles are only counted when they are shown
+        assert_eq!(target.collapsed, Some(2));
+        assert!(target.children.is_empty() && target.error.is_none());
+
+        // Without a Cargo.toml next to it, `target` is an ordinary directory
+        let docs = nodes.iter().find(|node| node.name == "docs").unwrap();
+        assert_eq!(docs.children[0].collapsed, None);
+        assert_eq!(docs.children[0].children.len(), 1);
+    }
+
+    #[test]
+    fn test_max_entries_summarises_the_rest() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        for (name, size) in [("a.txt", 10), ("b.txt", 20), ("c.txt", 30)] {
+            std::fs::write(dir.path().join(name), vec![0u8; size]).unwrap();
+        }
+
+        let (sort, filter) = (SortOptions::default(), EntryFilter::default());
+        let options = tree_options(&sort, &filter);
+        let nodes = TreeWalk::new(dir.path(), &options, None, None).collect().unwrap();
+        let annotations = TreeAnnotations {
+            max_entries: Some(1),
+            ..Default::default()
+        };
+        let mut lines = Vec::new();
+        render_tree(&nodes, "", &builtin_themes()[0], annotations, &mut lines);
+        let lines: Vec<_> = lines.iter().map(|line| strip_ansi_codes(line)).collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("├── ") && lines[0].ends_with("a.txt"), "{lines:?}");
+        assert_eq!(lines[1], "└── … 2 more entries (50 B)");
+    }
+
+    #[test]
+    fn test_tree_sizes_add_up() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join("src/deep")).unwrap();
+        std::fs::write(dir.path().join("README.md"), vec![0u8; 100]).unwrap();
+        std::fs::write(dir.path().join("src/main.rs"), vec![0u8; 30]).unwrap();
+        std::fs::write(dir.path().join("src/deep/mod.rs"), vec![0u8; 20]).unwrap();
+        std::fs::write(dir.path().join("src/.cache"), vec![0u8; 1000]).unwrap();
+
+        let (sort, filter) = (SortOptions::default(), EntryFilter::default());
+        let options = |max_depth| TreeOptions {
+            annotations: TreeAnnotations {
+                sizes: true,
+                counts: true,
+                max_entries: None,
+            },
+            max_depth,
+            ..tree_options(&sort, &filter)
+        };
+
+        let tree = read_root(dir.path(), &options(None)).unwrap();
+        let src = tree.nodes.iter().find(|node| node.name == "src").unwrap();
+        assert_eq!(src.total.unwrap().bytes, 50);
+        let mut summary = TreeSummary::default();
+        summary.add(&tree);
+        assert_eq!((summary.directories, summary.files, summary.size.bytes), (2, 3, 150));
+
+        // Directories cut off by --depth are measured in full, leaving out
+        // the same hidden files
+        for max_depth in [Some(1), Some(2)] {
+            let nodes = TreeWalk::new(dir.path(), &options(max_depth), None, None).collect().unwrap();
+            let src = nodes.iter().find(|node| node.name == "src").unwrap();
+            assert_eq!(src.total.unwrap(), DirSize { bytes: 50, partial: false });
+        }
+        let nodes = TreeWalk::new(dir.path(), &options(Some(1)), None, None).collect().unwrap();
+        assert!(nodes.iter().any(|node| node.name == "src" && node.truncated && node.children.is_empty()));
+        let all = TreeOptions {
+            hidden: HiddenFiles::AlmostAll,
+            ..options(Some(1))
+        };
+        let nodes = TreeWalk::new(dir.path(), &all, None, None).collect().unwrap();
+        let src = nodes.iter().find(|node| node.name == "src").unwrap();
+        assert_eq!(src.total.unwrap().bytes, 1050);
+
+        let collapsed = TreeOptions {
+            collapse: vec![CollapsedDir {
+                name: "deep".to_string(),
+                beside: None,
+            }],
+            ..options(None)
+        };
+        let nodes = TreeWalk::new(dir.path(), &collapsed, None, None).collect().unwrap();
+        let src = nodes.iter().find(|node| node.name == "src").unwrap();
+        let deep = src.children.iter().find(|node| node.name == "deep").unwrap();
+        assert_eq!(deep.collapsed, Some(1));
+        assert!(deep.children.is_empty());
+        assert_eq!(src.total.unwrap().bytes, 50);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_loops_end_the_tree() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+        std::fs::write(dir.path().join("sub/file.txt"), "").unwrap();
+        std::os::unix::fs::symlink("..", dir.path().join("sub/up")).unwrap();
+
+        let (sort, filter) = (SortOptions::default(), EntryFilter::default());
+        let options = |follow| TreeOptions {
+            traversal: Traversal {
+                follow,
+                one_file_system: false,
+            },
+            ..tree_options(&sort, &filter)
+        };
+        let up = |nodes: &[super::TreeNode]| {
+            let sub = nodes.iter().find(|node| node.name == "sub").unwrap();
+            let up = sub.children.iter().find(|node| node.name == "up").unwrap();
+            (up.children.len(), up.error.clone(), up.target.clone())
+        };
+
+        // Without --follow the link is a leaf showing its target
+        let (unfollowed, followed) = (options(false), options(true));
+        let mut visited = Visited::new(dir_id(dir.path()));
+        let walk = TreeWalk::new(dir.path(), &unfollowed, None, None);
+        let nodes = collect_tree(dir.path(), 0, &walk, &mut visited).unwrap();
+        assert_eq!(up(&nodes), (0, None, Some("..".into())));
+
+        let walk = TreeWalk::new(dir.path(), &followed, None, None);
+        let nodes = collect_tree(dir.path(), 0, &walk, &mut visited).unwrap();
+        assert_eq!(up(&nodes), (0, Some("symlink loop".to_string()), Some("..".into())));
+        assert_eq!(visited.ancestors.len(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_linked_directories_count_once() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join("real")).unwrap();
+        std::fs::write(dir.path().join("real/f"), vec![0u8; 1000]).unwrap();
+        std::os::unix::fs::symlink("real", dir.path().join("link")).unwrap();
+
+        let (sort, filter) = (SortOptions::default(), EntryFilter::default());
+        let options = |max_depth| TreeOptions {
+            annotations: TreeAnnotations {
+                sizes: true,
+                ..TreeAnnotations::default()
+            },
+            max_depth,
+            traversal: Traversal {
+                follow: true,
+                one_file_system: false,
+            },
+            ..tree_options(&sort, &filter)
+        };
+
+        // Both are shown with their size, but the footer counts them once,
+        // also when they are measured below --depth
+        for max_depth in [None, Some(1)] {
+            let tree = read_root(dir.path(), &options(max_depth)).unwrap();
+            let totals: Vec<_> = tree.nodes.iter().map(|node| node.total.unwrap().bytes).collect();
+            assert_eq!(totals, [1000, 1000]);
+            let mut summary = TreeSummary::default();
+            summary.add(&tree);
+            assert_eq!(summary.size.bytes, 1000);
+        }
+    }
+}
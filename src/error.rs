@@ -0,0 +1,168 @@
+use std::env;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Exit status when everything worked.
+pub const EXIT_OK: u8 = 0;
+/// Exit status for minor problems, e.g. an entry that could not be read.
+/// The rest of the output is still printed.
+pub const EXIT_MINOR: u8 = 1;
+/// Exit status for serious trouble, e.g. a directory or file given on the
+/// command line that cannot be read. Also used by clap for usage errors.
+pub const EXIT_SERIOUS: u8 = 2;
+
+static MINOR_PROBLEMS: AtomicBool = AtomicBool::new(false);
+static SERIOUS_PROBLEMS: AtomicBool = AtomicBool::new(false);
+
+/// Notes a problem that did not stop the command, so `lsa` exits with
+/// `EXIT_MINOR` once the output is complete.
+pub fn report_minor_problem() {
+    MINOR_PROBLEMS.store(true, Ordering::Relaxed);
+}
+
+pub fn had_minor_problems() -> bool {
+    MINOR_PROBLEMS.load(Ordering::Relaxed)
+}
+
+/// Prints an error that only affects part of the command, e.g. one of
+/// several paths, and makes `lsa` exit with `EXIT_SERIOUS` at the end.
+pub fn report_serious_problem(error: &LsaError) {
+    eprintln!("Error: {error}");
+    SERIOUS_PROBLEMS.store(true, Ordering::Relaxed);
+}
+
+pub fn had_serious_problems() -> bool {
+    SERIOUS_PROBLEMS.load(Ordering::Relaxed)
+}
+
+/// Errors that stop a command. Each one ends with `EXIT_SERIOUS`.
+#[derive(Debug)]
+pub enum LsaError {
+    /// Reading or writing `path` failed.
+    Io { path: PathBuf, source: io::Error },
+    /// The working directory was removed or cannot be read.
+    CurrentDir(io::Error),
+    /// `path` exists but is not what the command needs.
+    WrongKind { path: PathBuf, expected: &'static str },
+    /// A structured data file could not be parsed.
+    Parse { path: PathBuf, message: String },
+    UnsupportedFormat { path: PathBuf, extension: String },
+    Environment {
+        variable: &'static str,
+        source: env::VarError,
+    },
+    /// An external program such as `lscpu` failed.
+    Command { program: &'static str, message: String },
+    ThemeNotFound(String),
+    Theme(String),
+    Clipboard(String),
+    Config(String),
+    InvalidArgument(String),
+}
+
+impl LsaError {
+    pub fn io(path: &Path, source: io::Error) -> Self {
+        LsaError::Io {
+            path: path.to_path_buf(),
+            source,
+        }
+    }
+}
+
+impl fmt::Display for LsaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LsaError::Io { path, source } => {
+                write!(f, "cannot access '{}': {}", path.display(), describe_io_error(source))
+            }
+            LsaError::CurrentDir(source) => write!(
+                f,
+                "cannot read the current directory: {}",
+                describe_io_error(source)
+            ),
+            LsaError::WrongKind { path, expected } => {
+                write!(f, "'{}' is not a {expected}", path.display())
+            }
+            LsaError::Parse { path, message } => {
+                write!(f, "cannot parse '{}': {message}", path.display())
+            }
+            LsaError::UnsupportedFormat { path, extension } => write!(
+                f,
+                "unsupported file format '{extension}' for '{}' (expected json, yaml or toml)",
+                path.display()
+            ),
+            LsaError::Environment { variable, source } => {
+                write!(f, "cannot read the {variable} environment variable: {source}")
+            }
+            LsaError::Command { program, message } => write!(f, "{program} failed: {message}"),
+            LsaError::ThemeNotFound(name) => write!(f, "theme '{name}' not found"),
+            LsaError::Theme(message) => write!(f, "{message}"),
+            LsaError::Clipboard(message) => write!(f, "cannot copy to clipboard: {message}"),
+            LsaError::Config(message) => write!(f, "cannot save config: {message}"),
+            LsaError::InvalidArgument(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for LsaError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LsaError::Io { source, .. } | LsaError::CurrentDir(source) => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Short lowercase description of an I/O error without the `(os error N)`
+/// suffix, for messages and the inline errors in listings.
+pub fn describe_io_error(error: &io::Error) -> String {
+    match error.kind() {
+        io::ErrorKind::NotFound => "no such file or directory".to_string(),
+        io::ErrorKind::PermissionDenied => "permission denied".to_string(),
+        io::ErrorKind::NotADirectory => "not a directory".to_string(),
+        _ => {
+            let message = error.to_string();
+            let message = match message.find(" (os error") {
+                Some(end) => message[..end].to_string(),
+                None => message,
+            };
+            let mut chars = message.chars();
+            match chars.next() {
+                Some(first) => first.to_lowercase().chain(chars).collect(),
+                None => message,
+            }
+        }
+    }
+}
+
+/// Restores the default `SIGPIPE` behaviour so `lsa | head` ends quietly
+/// like other command line tools instead of panicking in `println!`.
+pub fn reset_sigpipe() {
+    #[cfg(unix)]
+    // SAFETY: called once at startup before any other thread exists
+    unsafe {
+        libc::signal(libc::SIGPIPE, libc::SIG_DFL);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LsaError, describe_io_error};
+    use std::io;
+    use std::path::Path;
+
+    #[test]
+    fn test_io_error_messages_are_short() {
+        let denied = io::Error::from(io::ErrorKind::PermissionDenied);
+        assert_eq!(describe_io_error(&denied), "permission denied");
+
+        let missing = io::Error::from(io::ErrorKind::NotFound);
+        let error = LsaError::io(Path::new("missing"), missing);
+        assert_eq!(
+            error.to_string(),
+            "cannot access 'missing': no such file or directory"
+        );
+    }
+}
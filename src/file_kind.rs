@@ -0,0 +1,145 @@
+use std::fs;
+use std::path::Path;
+
+/// What an entry is on disk, looked up without following symlinks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileKind {
+    Directory,
+    File,
+    Executable,
+    SymlinkDir,
+    SymlinkFile,
+    BrokenSymlink,
+    Pipe,
+    Socket,
+    CharDevice,
+    BlockDevice,
+    Special,
+}
+
+impl FileKind {
+    /// Classifies `path` with `lstat`. Entries that cannot be read are
+    /// treated as plain files.
+    pub fn of(path: &Path) -> FileKind {
+        match fs::symlink_metadata(path) {
+            Ok(metadata) => FileKind::from_metadata(path, &metadata),
+            Err(_) => FileKind::File,
+        }
+    }
+
+    /// Classifies an entry from metadata gathered with `symlink_metadata`.
+    /// `path` is only used to see where a symlink points.
+    pub fn from_metadata(path: &Path, metadata: &fs::Metadata) -> FileKind {
+        let file_type = metadata.file_type();
+        if file_type.is_symlink() {
+            return match fs::metadata(path) {
+                Ok(target) if target.is_dir() => FileKind::SymlinkDir,
+                Ok(_) => FileKind::SymlinkFile,
+                Err(_) => FileKind::BrokenSymlink,
+            };
+        }
+        if file_type.is_dir() {
+            return FileKind::Directory;
+        }
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+
+            if file_type.is_fifo() {
+                return FileKind::Pipe;
+            }
+            if file_type.is_socket() {
+                return FileKind::Socket;
+            }
+            if file_type.is_char_device() {
+                return FileKind::CharDevice;
+            }
+            if file_type.is_block_device() {
+                return FileKind::BlockDevice;
+            }
+            if file_type.is_file() && metadata.permissions().mode() & 0o111 != 0 {
+                return FileKind::Executable;
+            }
+        }
+
+        if file_type.is_file() {
+            FileKind::File
+        } else {
+            FileKind::Special
+        }
+    }
+
+    pub fn is_symlink(&self) -> bool {
+        matches!(
+            self,
+            FileKind::SymlinkDir | FileKind::SymlinkFile | FileKind::BrokenSymlink
+        )
+    }
+
+    /// Directories and symlinks to directories, which are listed and colored
+    /// as directories.
+    pub fn is_dir(&self) -> bool {
+        matches!(self, FileKind::Directory | FileKind::SymlinkDir)
+    }
+
+    /// Text for the Type column.
+    pub fn label(&self) -> &'static str {
+        match self {
+            FileKind::Directory => "Directory",
+            FileKind::File => "File",
+            FileKind::Executable => "Executable",
+            FileKind::SymlinkDir => "Symlink (dir)",
+            FileKind::SymlinkFile => "Symlink",
+            FileKind::BrokenSymlink => "Broken link",
+            FileKind::Pipe => "Pipe",
+            FileKind::Socket => "Socket",
+            FileKind::CharDevice => "Char device",
+            FileKind::BlockDevice => "Block device",
+            FileKind::Special => "Special",
+        }
+    }
+
+    /// Value of the `type` column in the machine-readable formats.
+    pub fn id(&self) -> &'static str {
+        match self {
+            FileKind::Directory => "directory",
+            FileKind::File => "file",
+            FileKind::Executable => "executable",
+            FileKind::SymlinkDir | FileKind::SymlinkFile => "symlink",
+            FileKind::BrokenSymlink => "broken_symlink",
+            FileKind::Pipe => "pipe",
+            FileKind::Socket => "socket",
+            FileKind::CharDevice => "char_device",
+            FileKind::BlockDevice => "block_device",
+            FileKind::Special => "special",
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::FileKind;
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::Path;
+
+    #[test]
+    fn test_classifies_special_files() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let script = dir.path().join("run");
+        fs::write(&script, "#!/bin/sh\n").unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+        std::os::unix::fs::symlink(dir.path(), dir.path().join("self")).unwrap();
+        std::os::unix::fs::symlink("missing", dir.path().join("broken")).unwrap();
+        let socket = std::os::unix::net::UnixListener::bind(dir.path().join("sock")).unwrap();
+
+        assert_eq!(FileKind::of(&script), FileKind::Executable);
+        assert_eq!(FileKind::of(&dir.path().join("self")), FileKind::SymlinkDir);
+        assert_eq!(FileKind::of(&dir.path().join("broken")), FileKind::BrokenSymlink);
+        assert_eq!(FileKind::of(&dir.path().join("sock")), FileKind::Socket);
+        assert_eq!(FileKind::of(Path::new("/dev/null")), FileKind::CharDevice);
+        assert_eq!(FileKind::of(dir.path()), FileKind::Directory);
+        drop(socket);
+    }
+}
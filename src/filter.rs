@@ -0,0 +1,278 @@
+use std::fs;
+use std::time::{Duration, SystemTime};
+
+use clap::ValueEnum;
+use globset::{Glob, GlobBuilder, GlobMatcher};
+use regex::Regex;
+
+use crate::file_kind::FileKind;
+use crate::query::Query;
+
+/// Entry types accepted by `--type`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum EntryType {
+    File,
+    Dir,
+    Symlink,
+    Executable,
+}
+
+impl EntryType {
+    pub fn matches(&self, kind: FileKind) -> bool {
+        match self {
+            EntryType::File => matches!(kind, FileKind::File | FileKind::Executable),
+            EntryType::Dir => kind.is_dir(),
+            EntryType::Symlink => kind.is_symlink(),
+            EntryType::Executable => kind == FileKind::Executable,
+        }
+    }
+}
+
+/// Which dotfiles a listing shows.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HiddenFiles {
+    #[default]
+    Hide,
+    /// `--almost-all`: dotfiles, but not `.` and `..`
+    AlmostAll,
+    /// `--all`: dotfiles, and `.` and `..` in tables and grids
+    All,
+}
+
+impl HiddenFiles {
+    pub fn shows_dotfiles(&self) -> bool {
+        *self != HiddenFiles::Hide
+    }
+}
+
+/// What the filters see of one entry. Metadata fields are `None` when they
+/// are unknown: `kind` when the entry could not be read, `size` e.g. for
+/// directories without `--dir-sizes`.
+pub struct EntryInfo<'a> {
+    pub name: &'a str,
+    pub kind: Option<FileKind>,
+    pub size: Option<u64>,
+    pub modified: Option<SystemTime>,
+    // Unix permission bits
+    pub mode: Option<u32>,
+}
+
+impl<'a> EntryInfo<'a> {
+    pub fn new(name: &'a str, kind: FileKind, metadata: &fs::Metadata, size: Option<u64>) -> Self {
+        #[cfg(unix)]
+        let mode = {
+            use std::os::unix::fs::PermissionsExt;
+            Some(metadata.permissions().mode())
+        };
+        #[cfg(not(unix))]
+        let mode = None;
+
+        EntryInfo {
+            name,
+            kind: Some(kind),
+            size,
+            modified: metadata.modified().ok(),
+            mode,
+        }
+    }
+
+    /// An entry whose metadata could not be read.
+    pub fn unreadable(name: &'a str) -> Self {
+        EntryInfo {
+            name,
+            kind: None,
+            size: None,
+            modified: None,
+            mode: None,
+        }
+    }
+}
+
+/// Which entries a listing keeps. Every condition that is set has to hold;
+/// within `names` and `types` one match is enough.
+#[derive(Default)]
+pub struct EntryFilter {
+    pub names: Vec<GlobMatcher>,
+    pub regex: Option<Regex>,
+    pub types: Vec<EntryType>,
+    pub min_size: Option<u64>,
+    pub max_size: Option<u64>,
+    pub modified_after: Option<SystemTime>,
+    pub modified_before: Option<SystemTime>,
+    // `--where` expression
+    pub query: Option<Query>,
+}
+
+impl EntryFilter {
+    /// Filter with the age limits of `--newer`/`--older` resolved against now.
+    pub fn with_ages(mut self, newer: Option<Duration>, older: Option<Duration>) -> Self {
+        let now = SystemTime::now();
+        let cutoff = |age: Duration| now.checked_sub(age).unwrap_or(SystemTime::UNIX_EPOCH);
+        self.modified_after = newer.map(cutoff);
+        self.modified_before = older.map(cutoff);
+        self
+    }
+
+    /// Whether any condition besides the name is set, which needs metadata
+    /// to check.
+    fn needs_metadata(&self) -> bool {
+        self.query.is_some()
+            || !self.types.is_empty()
+            || self.min_size.is_some()
+            || self.max_size.is_some()
+            || self.modified_after.is_some()
+            || self.modified_before.is_some()
+    }
+
+    /// Entries whose metadata could not be read only pass filters on the
+    /// name.
+    pub fn matches(&self, entry: &EntryInfo) -> bool {
+        let name = entry.name;
+        if !self.names.is_empty() && !self.names.iter().any(|glob| glob.is_match(name)) {
+            return false;
+        }
+        if let Some(regex) = &self.regex
+            && !regex.is_match(name)
+        {
+            return false;
+        }
+
+        let Some(kind) = entry.kind else {
+            return !self.needs_metadata();
+        };
+        if !self.types.is_empty() && !self.types.iter().any(|t| t.matches(kind)) {
+            return false;
+        }
+
+        let size_ok = |limit: Option<u64>, ok: fn(u64, u64) -> bool| match limit {
+            Some(limit) => entry.size.is_some_and(|size| ok(size, limit)),
+            None => true,
+        };
+        let time_ok = |limit: Option<SystemTime>, ok: fn(&SystemTime, &SystemTime) -> bool| {
+            match limit {
+                Some(limit) => entry.modified.is_some_and(|modified| ok(&modified, &limit)),
+                None => true,
+            }
+        };
+        size_ok(self.min_size, |size, min| size >= min)
+            && size_ok(self.max_size, |size, max| size <= max)
+            && time_ok(self.modified_after, SystemTime::ge)
+            && time_ok(self.modified_before, SystemTime::lt)
+            && self.query.as_ref().is_none_or(|query| query.matches(entry))
+    }
+}
+
+/// Parses a size such as `512`, `10K`, `1.5M` or `2GiB`. Units are powers of
+/// 1024 like the sizes shown in the table.
+pub fn parse_size(value: &str) -> Result<u64, String> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: f64 = number
+        .parse()
+        .map_err(|_| format!("invalid size \"{value}\", expected e.g. 512, 10K or 1.5M"))?;
+
+    let multiplier: u64 = match unit.trim().to_lowercase().as_str() {
+        "" | "b" => 1,
+        "k" | "kb" | "kib" => 1 << 10,
+        "m" | "mb" | "mib" => 1 << 20,
+        "g" | "gb" | "gib" => 1 << 30,
+        "t" | "tb" | "tib" => 1 << 40,
+        other => return Err(format!("unknown size unit \"{other}\", expected B, K, M, G or T")),
+    };
+    Ok((number * multiplier as f64).round() as u64)
+}
+
+/// Parses an age such as `30s`, `15m`, `2h`, `3d`, `2w`, `6mo` or `1y`.
+/// Months are 30 days and years 365 days.
+pub fn parse_age(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: u64 = number
+        .parse()
+        .map_err(|_| format!("invalid age \"{value}\", expected e.g. 2h, 3d or 1y"))?;
+
+    let seconds: u64 = match unit.trim().to_lowercase().as_str() {
+        "s" | "sec" => 1,
+        "m" | "min" => 60,
+        "h" => 3600,
+        "d" => 86400,
+        "w" => 7 * 86400,
+        "mo" => 30 * 86400,
+        "y" => 365 * 86400,
+        "" => return Err(format!("age \"{value}\" needs a unit: s, m, h, d, w, mo or y")),
+        other => {
+            return Err(format!(
+                "unknown age unit \"{other}\", expected s, m, h, d, w, mo or y"
+            ));
+        }
+    };
+    Ok(Duration::from_secs(number.saturating_mul(seconds)))
+}
+
+/// A glob for paths, where `*` and `?` stay within one component and `**`
+/// crosses them.
+pub fn parse_path_glob(value: &str) -> Result<GlobMatcher, String> {
+    GlobBuilder::new(value)
+        .literal_separator(true)
+        .build()
+        .map(|glob| glob.compile_matcher())
+        .map_err(|e| e.to_string())
+}
+
+pub fn parse_glob(value: &str) -> Result<GlobMatcher, String> {
+    Glob::new(value)
+        .map(|glob| glob.compile_matcher())
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EntryFilter, EntryInfo, EntryType, parse_age, parse_glob, parse_size};
+    use crate::file_kind::FileKind;
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn test_parse_size_and_age() {
+        assert_eq!(parse_size("512"), Ok(512));
+        assert_eq!(parse_size("10K"), Ok(10 * 1024));
+        assert_eq!(parse_size("1.5mb"), Ok(1536 * 1024));
+        assert!(parse_size("10X").unwrap_err().contains("\"x\""));
+        assert!(parse_size("M").is_err());
+
+        assert_eq!(parse_age("2d"), Ok(Duration::from_secs(2 * 86400)));
+        assert_eq!(parse_age("6mo"), Ok(Duration::from_secs(180 * 86400)));
+        assert!(parse_age("7").unwrap_err().contains("needs a unit"));
+    }
+
+    #[test]
+    fn test_filter_combines_conditions() {
+        let filter = EntryFilter {
+            names: vec![parse_glob("*.rs").unwrap()],
+            types: vec![EntryType::File],
+            min_size: Some(100),
+            ..Default::default()
+        }
+        .with_ages(Some(Duration::from_secs(3600)), None);
+        let entry = |name, kind, size: Option<u64>, age: u64| EntryInfo {
+            name,
+            kind: Some(kind),
+            size,
+            modified: SystemTime::now().checked_sub(Duration::from_secs(age)),
+            mode: None,
+        };
+
+        assert!(filter.matches(&entry("main.rs", FileKind::File, Some(200), 0)));
+        assert!(!filter.matches(&entry("main.rs", FileKind::File, Some(50), 0)));
+        assert!(!filter.matches(&entry("main.py", FileKind::File, Some(200), 0)));
+        assert!(!filter.matches(&entry("src.rs", FileKind::Directory, None, 0)));
+        assert!(!filter.matches(&entry("main.rs", FileKind::File, Some(200), 7200)));
+        // Unreadable entries cannot prove their type or size
+        assert!(!filter.matches(&EntryInfo::unreadable("main.rs")));
+    }
+}
@@ -0,0 +1,234 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::themes::Theme;
+
+/// Git state of a single entry. The variants are ordered by how important they
+/// are when summarising a directory: a folder containing one conflicted file
+/// is reported as conflicted even if everything else in it is just modified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum GitStatus {
+    Clean,
+    Ignored,
+    Untracked,
+    Staged,
+    Modified,
+    Conflicted,
+}
+
+impl GitStatus {
+    pub fn marker(&self) -> &'static str {
+        match self {
+            GitStatus::Clean => "",
+            GitStatus::Ignored => "!",
+            GitStatus::Untracked => "?",
+            GitStatus::Staged => "S",
+            GitStatus::Modified => "M",
+            GitStatus::Conflicted => "U",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            GitStatus::Clean => "clean",
+            GitStatus::Ignored => "ignored",
+            GitStatus::Untracked => "untracked",
+            GitStatus::Staged => "staged",
+            GitStatus::Modified => "modified",
+            GitStatus::Conflicted => "conflicted",
+        }
+    }
+
+    pub fn color(&self, theme: &Theme) -> (u8, u8, u8) {
+        match self {
+            // Clean entries have no marker, so this is never visible
+            GitStatus::Clean => theme.file_name,
+            GitStatus::Ignored => theme.git_ignored_color(),
+            GitStatus::Untracked => theme.git_untracked_color(),
+            GitStatus::Staged => theme.git_staged_color(),
+            GitStatus::Modified => theme.git_modified_color(),
+            GitStatus::Conflicted => theme.git_conflicted_color(),
+        }
+    }
+
+    fn from_porcelain(x: u8, y: u8) -> GitStatus {
+        match (x, y) {
+            (b'?', b'?') => GitStatus::Untracked,
+            (b'!', b'!') => GitStatus::Ignored,
+            (b'U', _) | (_, b'U') | (b'A', b'A') | (b'D', b'D') => GitStatus::Conflicted,
+            (_, b'M') | (_, b'D') | (_, b'T') => GitStatus::Modified,
+            _ => GitStatus::Staged,
+        }
+    }
+}
+
+/// Snapshot of `git status` for the work tree containing a directory. Only the
+/// local repository is read; nothing touches the network.
+pub struct GitRepo {
+    root: PathBuf,
+    statuses: HashMap<PathBuf, GitStatus>,
+    // Untracked and ignored directories are reported as a whole by git
+    directories: HashMap<PathBuf, GitStatus>,
+}
+
+impl GitRepo {
+    /// Returns `None` when `dir` is not inside a git work tree or git is not
+    /// installed, so callers can simply skip the git column.
+    pub fn discover(dir: &Path) -> Option<GitRepo> {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(["rev-parse", "--is-inside-work-tree", "--show-toplevel"])
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let mut lines = stdout.lines();
+        if lines.next()? != "true" {
+            return None;
+        }
+        let root = PathBuf::from(lines.next()?);
+
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(&root)
+            .args([
+                "status",
+                "--porcelain=v1",
+                "-z",
+                "--ignored",
+                "--untracked-files=normal",
+            ])
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+
+        let mut repo = GitRepo {
+            root,
+            statuses: HashMap::new(),
+            directories: HashMap::new(),
+        };
+        repo.parse_porcelain(&output.stdout);
+        Some(repo)
+    }
+
+    fn parse_porcelain(&mut self, output: &[u8]) {
+        let mut records = output.split(|&b| b == 0).filter(|r| !r.is_empty());
+
+        while let Some(record) = records.next() {
+            if record.len() < 4 {
+                continue;
+            }
+            let (x, y) = (record[0], record[1]);
+            let path = String::from_utf8_lossy(&record[3..]).to_string();
+
+            // Renames and copies are followed by the original path
+            if x == b'R' || x == b'C' {
+                records.next();
+            }
+
+            let status = GitStatus::from_porcelain(x, y);
+            let relative = PathBuf::from(path.trim_end_matches('/'));
+            if path.ends_with('/') {
+                self.directories.insert(relative.clone(), status);
+            }
+
+            if status != GitStatus::Ignored {
+                // Propagate to every parent so directory lookups are O(1)
+                for ancestor in relative.ancestors().skip(1) {
+                    if ancestor.as_os_str().is_empty() {
+                        break;
+                    }
+                    let entry = self
+                        .statuses
+                        .entry(ancestor.to_path_buf())
+                        .or_insert(status);
+                    *entry = (*entry).max(status);
+                }
+            }
+            let entry = self.statuses.entry(relative).or_insert(status);
+            *entry = (*entry).max(status);
+        }
+    }
+
+    /// Status of `path`, where directories report the most important status of
+    /// anything below them.
+    pub fn status(&self, path: &Path) -> GitStatus {
+        self.lookup(path).unwrap_or(GitStatus::Clean)
+    }
+
+    /// Like `status`, but `None` for paths outside this work tree.
+    pub fn lookup(&self, path: &Path) -> Option<GitStatus> {
+        let relative = self.relative_path(path)?;
+
+        if let Some(status) = self.statuses.get(&relative) {
+            return Some(*status);
+        }
+
+        Some(
+            relative
+                .ancestors()
+                .find_map(|ancestor| self.directories.get(ancestor).copied())
+                .unwrap_or(GitStatus::Clean),
+        )
+    }
+
+    fn relative_path(&self, path: &Path) -> Option<PathBuf> {
+        // Canonicalize the parent only, so symlinks are looked up as themselves
+        let parent = match path.parent() {
+            Some(p) if !p.as_os_str().is_empty() => {
+                p.canonicalize().or_else(|_| std::path::absolute(p)).ok()?
+            }
+            _ => std::env::current_dir().ok()?,
+        };
+        let absolute = match path.file_name() {
+            Some(name) => parent.join(name),
+            None => path.canonicalize().ok()?,
+        };
+        absolute
+            .strip_prefix(&self.root)
+            .ok()
+            .map(|p| p.to_path_buf())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GitRepo, GitStatus};
+    use std::collections::HashMap;
+    use std::path::{Path, PathBuf};
+
+    fn repo_from(porcelain: &[u8]) -> GitRepo {
+        let mut repo = GitRepo {
+            root: PathBuf::from("/repo"),
+            statuses: HashMap::new(),
+            directories: HashMap::new(),
+        };
+        repo.parse_porcelain(porcelain);
+        repo
+    }
+
+    #[test]
+    fn test_directory_summarises_children() {
+        let repo = repo_from(b" M src/a.rs\0UU src/b.rs\0A  docs/c.md\0");
+        assert_eq!(repo.status(Path::new("/repo/src/a.rs")), GitStatus::Modified);
+        assert_eq!(repo.status(Path::new("/repo/src")), GitStatus::Conflicted);
+        assert_eq!(repo.status(Path::new("/repo/docs")), GitStatus::Staged);
+        assert_eq!(repo.status(Path::new("/repo/README.md")), GitStatus::Clean);
+    }
+
+    #[test]
+    fn test_untracked_and_ignored_directories_cover_contents() {
+        let repo = repo_from(b"?? new/\0!! target/\0R  renamed.rs\0old.rs\0");
+        assert_eq!(repo.status(Path::new("/repo/new/file.rs")), GitStatus::Untracked);
+        assert_eq!(repo.status(Path::new("/repo/target/debug")), GitStatus::Ignored);
+        assert_eq!(repo.status(Path::new("/repo/renamed.rs")), GitStatus::Staged);
+        assert_eq!(repo.status(Path::new("/repo/old.rs")), GitStatus::Clean);
+    }
+}
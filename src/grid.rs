@@ -0,0 +1,200 @@
+use std::env;
+use std::io::{self, IsTerminal};
+
+use clap::ValueEnum;
+use crossterm::terminal;
+use unicode_width::UnicodeWidthChar;
+
+/// How `--grid` fills its columns.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum GridOrder {
+    /// Top to bottom, then the next column, like `ls`
+    Down,
+    /// Left to right, then the next row, like `ls -x`
+    Across,
+}
+
+// Spaces between two columns
+const SEPARATOR: usize = 2;
+
+/// One entry of the grid: the text as printed, possibly with color codes,
+/// and the number of terminal cells it takes.
+pub struct GridCell {
+    pub text: String,
+    pub width: usize,
+}
+
+/// Columns available for the grid: the terminal width, `$COLUMNS` when the
+/// output is not a terminal, and 80 otherwise.
+pub fn terminal_width() -> usize {
+    if io::stdout().is_terminal()
+        && let Ok((width, _)) = terminal::size()
+    {
+        return width as usize;
+    }
+    env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.parse().ok())
+        .filter(|&columns| columns > 0)
+        .unwrap_or(80)
+}
+
+/// Terminal cells taken by `text`. Nerd Font icons live in the private use
+/// areas and are drawn two cells wide by most patched fonts.
+pub fn display_width(text: &str) -> usize {
+    text.chars().map(char_width).sum()
+}
+
+fn char_width(c: char) -> usize {
+    match c as u32 {
+        0xE000..=0xF8FF | 0xF0000..=0xFFFFD | 0x100000..=0x10FFFD => 2,
+        _ => c.width().unwrap_or(0),
+    }
+}
+
+/// Cuts `text` to at most `width` terminal cells. Color codes take no room
+/// and are all kept, so a reset after the cut still applies.
+pub fn clip_to_width(text: &str, width: usize) -> String {
+    let mut clipped = String::with_capacity(text.len());
+    let mut used = 0;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            clipped.push(c);
+            // CSI sequences end with a byte in @..~, after the `[`
+            if let Some(next) = chars.next() {
+                clipped.push(next);
+                if next == '[' {
+                    for c in chars.by_ref() {
+                        clipped.push(c);
+                        if ('@'..='~').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+            }
+            continue;
+        }
+        let cells = char_width(c);
+        if used + cells <= width {
+            clipped.push(c);
+            used += cells;
+        } else {
+            // Nothing visible after the cut, not even narrower characters
+            used = width;
+        }
+    }
+    clipped
+}
+
+/// Lays the cells out in as many columns as fit in `width` and returns the
+/// lines. Falls back to one entry per line when even two columns are too
+/// wide.
+pub fn render_grid(cells: &[GridCell], width: usize, order: GridOrder) -> Vec<String> {
+    if cells.is_empty() {
+        return Vec::new();
+    }
+    let (rows, column_widths) = layout(cells, width, order);
+    let columns = column_widths.len();
+
+    (0..rows)
+        .map(|row| {
+            let mut line = String::new();
+            for (column, column_width) in column_widths.iter().enumerate() {
+                let Some(cell) = cell_index(row, column, rows, columns, order).and_then(|i| cells.get(i))
+                else {
+                    continue;
+                };
+                if column > 0 {
+                    line.push_str(&" ".repeat(SEPARATOR));
+                }
+                line.push_str(&cell.text);
+                let is_last = cell_index(row, column + 1, rows, columns, order)
+                    .is_none_or(|next| next >= cells.len());
+                if !is_last {
+                    line.push_str(&" ".repeat(column_width - cell.width));
+                }
+            }
+            line
+        })
+        .collect()
+}
+
+/// Number of rows and the width of each column for the widest layout that
+/// fits.
+fn layout(cells: &[GridCell], width: usize, order: GridOrder) -> (usize, Vec<usize>) {
+    let narrowest = cells.iter().map(|cell| cell.width).min().unwrap_or(0);
+    let most_columns = ((width + SEPARATOR) / (narrowest + SEPARATOR).max(1)).clamp(1, cells.len());
+
+    for columns in (2..=most_columns).rev() {
+        let rows = cells.len().div_ceil(columns);
+        // Going down, fewer columns may be enough for this number of rows
+        let columns = match order {
+            GridOrder::Down => cells.len().div_ceil(rows),
+            GridOrder::Across => columns,
+        };
+        let mut column_widths = vec![0; columns];
+        for (i, cell) in cells.iter().enumerate() {
+            let column = match order {
+                GridOrder::Down => i / rows,
+                GridOrder::Across => i % columns,
+            };
+            column_widths[column] = column_widths[column].max(cell.width);
+        }
+        let total: usize = column_widths.iter().sum::<usize>() + SEPARATOR * (columns - 1);
+        if total <= width {
+            return (rows, column_widths);
+        }
+    }
+    let widest = cells.iter().map(|cell| cell.width).max().unwrap_or(0);
+    (cells.len(), vec![widest])
+}
+
+fn cell_index(row: usize, column: usize, rows: usize, columns: usize, order: GridOrder) -> Option<usize> {
+    if column >= columns {
+        return None;
+    }
+    Some(match order {
+        GridOrder::Down => column * rows + row,
+        GridOrder::Across => row * columns + column,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GridCell, GridOrder, clip_to_width, display_width, render_grid};
+
+    fn cells(names: &[&str]) -> Vec<GridCell> {
+        names
+            .iter()
+            .map(|name| GridCell {
+                text: name.to_string(),
+                width: display_width(name),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_grid_fills_down_and_across() {
+        let names = cells(&["a", "bb", "ccc", "d", "e"]);
+        assert_eq!(render_grid(&names, 12, GridOrder::Down), ["a   ccc  e", "bb  d"]);
+        assert_eq!(render_grid(&names, 12, GridOrder::Across), ["a  bb  ccc", "d  e"]);
+        // Too narrow for two columns
+        assert_eq!(render_grid(&names, 4, GridOrder::Down), ["a", "bb", "ccc", "d", "e"]);
+    }
+
+    #[test]
+    fn test_icons_are_two_cells_wide() {
+        assert_eq!(display_width("\u{e7a8} main.rs"), 10);
+        assert_eq!(display_width("日本"), 4);
+    }
+
+    #[test]
+    fn test_clip_keeps_color_codes() {
+        let line = "\x1b[38;2;1;2;3mabcdef\x1b[0m";
+        assert_eq!(clip_to_width(line, 3), "\x1b[38;2;1;2;3mabc\x1b[0m");
+        assert_eq!(clip_to_width(line, 10), line);
+        // A wide character that does not fit is left out whole
+        assert_eq!(clip_to_width("a日本", 2), "a");
+    }
+}
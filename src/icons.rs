@@ -1,5 +1,6 @@
 use std::path::Path;
 
+use crate::file_kind::FileKind;
 use crate::theme::{IconTheme, Theme};
 
 use lazy_static::lazy_static;
@@ -13,10 +14,10 @@ fn load_icon_theme() -> IconTheme {
     // Try to load from config directory
     if let Some(config_dir) = dirs::config_dir() {
         let config_path = config_dir.join("lsa").join("icon-theme.yaml");
-        if let Some(path_str) = config_path.to_str() {
-            if let Ok(theme) = Theme::from_path(path_str) {
-                return theme;
-            }
+        if let Some(path_str) = config_path.to_str()
+            && let Ok(theme) = Theme::from_path(path_str)
+        {
+            return theme;
         }
     }
 
@@ -29,18 +30,35 @@ lazy_static! {
 }
 
 pub fn get_file_icon(path: &Path) -> &'static str {
-    if path.is_dir() {
-        &ICON_THEME.filetype.dir
-    } else if let Some(extension) = path.extension().and_then(|s| s.to_str()) {
-        ICON_THEME.extension.get(&extension.to_lowercase()).map(|s| s.as_str()).unwrap_or(&ICON_THEME.filetype.file)
-    } else {
-        let filename = path
-            .file_name()
-            .and_then(|s| s.to_str())
-            .unwrap_or("")
-            .to_lowercase();
+    let name = path
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or("");
+    icon_for(name, FileKind::of(path))
+}
 
-        ICON_THEME.name.get(&filename).map(|s| s.as_str()).unwrap_or(&ICON_THEME.filetype.file)
+/// Icon for an entry of the given kind. Regular files and executables use
+/// the extension or file name icons when there is one.
+pub fn icon_for(name: &str, kind: FileKind) -> &'static str {
+    let filetype = &ICON_THEME.filetype;
+    match kind {
+        FileKind::Directory => &filetype.dir,
+        FileKind::SymlinkDir => &filetype.symlink_dir,
+        FileKind::SymlinkFile | FileKind::BrokenSymlink => &filetype.symlink_file,
+        FileKind::Pipe => &filetype.pipe,
+        FileKind::Socket => &filetype.socket,
+        FileKind::CharDevice => &filetype.device_char,
+        FileKind::BlockDevice => &filetype.device_block,
+        FileKind::Special => &filetype.special,
+        FileKind::Executable => named_icon(name).unwrap_or(&filetype.executable),
+        FileKind::File => named_icon(name).unwrap_or(&filetype.file),
     }
 }
 
+fn named_icon(name: &str) -> Option<&'static str> {
+    if let Some(extension) = Path::new(name).extension().and_then(|s| s.to_str()) {
+        ICON_THEME.extension.get(&extension.to_lowercase()).map(|s| s.as_str())
+    } else {
+        ICON_THEME.name.get(&name.to_lowercase()).map(|s| s.as_str())
+    }
+}
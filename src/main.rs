@@ -1,32 +1,59 @@
+mod color;
+mod columns;
 mod config;
+mod dirsize;
 mod display;
+mod error;
+mod file_kind;
+mod filter;
+mod git;
+mod grid;
 mod icons;
+mod output;
 mod parser;
+mod paths;
+mod query;
+mod sort;
 mod theme;
+mod theme_import;
+mod theme_lint;
+mod theme_picker;
 mod themes;
+mod user_themes;
 mod utils;
 mod workspace;
 
-use clap::{Parser, ValueEnum};
-use inquire::Select;
-
-use config::{Config, load_config, save_config};
-use display::{show_cpu_info, show_directory_table, show_help, show_path_table, show_structured_data, show_tree};
-use themes::{get_theme_by_name, get_themes};
-
-#[derive(Clone, ValueEnum)]
-pub enum SortBy {
-    Name,
-    Size,
-    Modified,
-    Type,
-}
+use std::env;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{ArgAction, Parser, Subcommand};
+use color::{ColorDepth, ColorMode};
+
+use columns::{Column, DEFAULT_COLUMNS};
+use config::{Config, DEFAULT_COLLAPSED, load_config, save_config};
+use dirsize::Traversal;
+use error::LsaError;
+use filter::{EntryFilter, EntryType, HiddenFiles};
+use grid::GridOrder;
+use output::OutputFormat;
+use sort::{SortBy, SortOptions};
+use theme_import::{SchemeFormat, import_theme};
+use theme_picker::pick_theme;
+use display::{
+    CollapsedDir, ListOptions, TreeAnnotations, TreeOptions, show_cpu_info, show_listing, show_help, show_path_table, show_structured_data,
+    show_theme_gallery, show_theme_lint, show_tree,
+};
+use themes::{Theme, get_theme_by_name, get_themes};
 
 #[derive(Parser)]
 #[command(name = "lsa")]
 #[command(about = "A colorful directory listing tool with multiple themes")]
-#[command(disable_help_flag = true)]
+#[command(disable_help_flag = true, disable_help_subcommand = true)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     #[arg(short, long)]
     help: bool,
 
@@ -54,9 +81,33 @@ struct Cli {
     #[arg(short, long, requires = "tree")]
     depth: Option<usize>,
 
-    #[arg(short = 'a', long, requires = "tree")]
+    #[arg(long, requires = "tree")]
+    sizes: bool,
+
+    #[arg(long, requires = "tree")]
+    counts: bool,
+
+    #[arg(long = "match", value_name = "GLOB", requires = "tree", value_parser = filter::parse_path_glob)]
+    patterns: Vec<globset::GlobMatcher>,
+
+    #[arg(long, requires = "tree")]
+    prune: bool,
+
+    #[arg(long, requires = "tree")]
+    dirs_only: bool,
+
+    #[arg(long, value_name = "N", requires = "tree")]
+    max_entries: Option<usize>,
+
+    #[arg(long, value_name = "NAMES", requires = "tree", value_delimiter = ',')]
+    expand: Vec<String>,
+
+    #[arg(short = 'a', long)]
     all: bool,
 
+    #[arg(short = 'A', long, conflicts_with = "all")]
+    almost_all: bool,
+
     #[arg(long, value_name = "THEME")]
     theme: Option<Option<String>>,
 
@@ -66,22 +117,266 @@ struct Cli {
     #[arg(long, value_name = "FILE_PATH")]
     open: Option<String>,
 
-    #[arg(long, value_name = "SORT_BY")]
-    sort: Option<crate::SortBy>,
+    #[arg(long, value_name = "KEYS", value_delimiter = ',')]
+    sort: Vec<SortBy>,
+
+    #[arg(short, long)]
+    reverse: bool,
+
+    #[arg(long)]
+    natural: bool,
+
+    #[arg(long)]
+    ignore_case: bool,
+
+    #[arg(long)]
+    dir_sizes: bool,
+
+    #[arg(short = 'L', long)]
+    dereference: bool,
+
+    #[arg(long)]
+    no_git: bool,
 
-    #[arg(value_name = "DIRECTORY")]
-    directory: Option<String>,
+    #[arg(long, value_name = "GLOB", value_parser = filter::parse_glob)]
+    name: Vec<globset::GlobMatcher>,
+
+    #[arg(long, value_name = "REGEX")]
+    regex: Option<regex::Regex>,
+
+    #[arg(long = "type", value_name = "TYPE", value_delimiter = ',')]
+    types: Vec<EntryType>,
+
+    #[arg(long, value_name = "SIZE", value_parser = filter::parse_size)]
+    min_size: Option<u64>,
+
+    // `--max-size` is taken by the workspace snapshot
+    #[arg(long, value_name = "SIZE", value_parser = filter::parse_size)]
+    max_entry_size: Option<u64>,
+
+    #[arg(long, value_name = "AGE", value_parser = filter::parse_age)]
+    newer: Option<std::time::Duration>,
+
+    #[arg(long, value_name = "AGE", value_parser = filter::parse_age)]
+    older: Option<std::time::Duration>,
+
+    #[arg(long = "where", value_name = "EXPR")]
+    query: Option<String>,
+
+    #[arg(long, value_name = "COLUMNS", value_delimiter = ',')]
+    columns: Vec<Column>,
+
+    #[arg(long, value_name = "ORDER", num_args = 0..=1, require_equals = true, default_missing_value = "down")]
+    grid: Option<GridOrder>,
+
+    #[arg(short = 'R', long)]
+    recursive: bool,
+
+    #[arg(long)]
+    git_ignore: bool,
+
+    #[arg(long, value_name = "N")]
+    limit: Option<usize>,
+
+    #[arg(long)]
+    follow: bool,
+
+    #[arg(long)]
+    one_file_system: bool,
+
+    #[arg(long, global = true, value_name = "FORMAT", default_value = "table")]
+    format: OutputFormat,
+
+    #[arg(long, global = true, value_name = "WHEN", default_value = "auto")]
+    color: ColorMode,
+
+    #[arg(long, global = true, value_name = "DEPTH")]
+    color_depth: Option<ColorDepth>,
+
+    #[arg(value_name = "PATH")]
+    paths: Vec<String>,
 }
 
-fn main() {
+#[derive(Subcommand)]
+enum Command {
+    /// Manage user themes
+    Theme {
+        /// Print help
+        #[arg(short, long, action = ArgAction::Help)]
+        help: Option<bool>,
+
+        #[command(subcommand)]
+        command: ThemeCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum ThemeCommand {
+    /// Create a user theme from a base16/base24 scheme or terminal color config
+    Import {
+        /// Print help
+        #[arg(short, long, action = ArgAction::Help)]
+        help: Option<bool>,
+
+        /// base16/base24 YAML, Alacritty TOML/YAML, kitty .conf or .itermcolors file
+        #[arg(value_name = "FILE")]
+        file: String,
+
+        /// Theme name, defaults to the scheme name or the file name
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Scheme format, detected from the extension when omitted
+        #[arg(long, value_name = "FORMAT")]
+        from: Option<SchemeFormat>,
+
+        /// Replace an existing user theme with the same name
+        #[arg(long)]
+        force: bool,
+    },
+    /// Check a theme for low contrast and colors that are hard to tell apart
+    Lint {
+        /// Print help
+        #[arg(short, long, action = ArgAction::Help)]
+        help: Option<bool>,
+
+        /// Theme to check, defaults to the configured theme
+        #[arg(value_name = "NAME")]
+        name: Option<String>,
+
+        /// Terminal background the theme is checked against
+        #[arg(long, value_name = "COLOR", default_value = theme_lint::DEFAULT_BACKGROUND)]
+        background: String,
+
+        /// Print an adjusted theme as TOML instead of the report
+        #[arg(long)]
+        fix: bool,
+    },
+    /// Print a sample listing and tree in every theme
+    Gallery {
+        /// Print help
+        #[arg(short, long, action = ArgAction::Help)]
+        help: Option<bool>,
+    },
+}
+
+fn main() -> ExitCode {
+    error::reset_sigpipe();
     let cli = Cli::parse();
+    color::init(cli.color, cli.color_depth);
+
+    match run(&cli) {
+        Ok(()) if error::had_serious_problems() => ExitCode::from(error::EXIT_SERIOUS),
+        Ok(()) if error::had_minor_problems() => ExitCode::from(error::EXIT_MINOR),
+        Ok(()) => ExitCode::from(error::EXIT_OK),
+        Err(e) => {
+            eprintln!("Error: {e}");
+            ExitCode::from(error::EXIT_SERIOUS)
+        }
+    }
+}
+
+fn default_theme() -> Theme {
+    let config = load_config();
+    get_theme_by_name(&config.default_theme).unwrap_or_else(|| get_themes()[0].clone())
+}
+
+/// The `columns` from config.toml, or the default columns when it is unset
+/// or invalid.
+fn configured_columns() -> Vec<Column> {
+    let Some(names) = load_config().columns else {
+        return DEFAULT_COLUMNS.to_vec();
+    };
+    columns::parse_columns(&names).unwrap_or_else(|e| {
+        eprintln!("Warning: {e} in config.toml, using the default columns");
+        DEFAULT_COLUMNS.to_vec()
+    })
+}
 
+/// Directory names the tree collapses: `collapse` from config.toml or the
+/// defaults, without the ones given to `--expand`.
+fn collapsed_directories(expand: &[String]) -> Vec<CollapsedDir> {
+    let configured: Vec<CollapsedDir> = match load_config().collapse {
+        Some(names) => names
+            .into_iter()
+            .map(|name| CollapsedDir { name, beside: None })
+            .collect(),
+        None => DEFAULT_COLLAPSED
+            .iter()
+            .map(|(name, beside)| CollapsedDir {
+                name: name.to_string(),
+                beside: beside.map(String::from),
+            })
+            .collect(),
+    };
+    configured
+        .into_iter()
+        .filter(|dir| !expand.contains(&dir.name))
+        .collect()
+}
+
+/// Expands the path arguments, reporting the ones that do not exist.
+fn expand_paths(args: &[String]) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    for arg in args {
+        match paths::expand_path(arg) {
+            Ok(found) => paths.extend(found),
+            Err(e) => error::report_serious_problem(&e),
+        }
+    }
+    paths
+}
+
+fn run(cli: &Cli) -> Result<(), LsaError> {
     if cli.help {
-        let config = load_config();
-        let theme =
-            get_theme_by_name(&config.default_theme).unwrap_or_else(|| get_themes()[0].clone());
-        show_help(&theme);
-        return;
+        show_help(&default_theme());
+        return Ok(());
+    }
+
+    if let Some(Command::Theme { command, .. }) = &cli.command {
+        match command {
+            ThemeCommand::Import {
+                file,
+                name,
+                from,
+                force,
+                ..
+            } => {
+                let theme = import_theme(std::path::Path::new(file), *from, name.as_deref())
+                    .map_err(LsaError::Theme)?;
+                let path = user_themes::save_user_theme(&theme, *force).map_err(LsaError::Theme)?;
+                println!("✓ Imported '{}' to {}", theme.name, path.display());
+                println!("  Use it with: lsa --theme {}", theme.name);
+            }
+            ThemeCommand::Lint {
+                name,
+                background,
+                fix,
+                ..
+            } => {
+                let Some(background) = user_themes::parse_hex(background) else {
+                    return Err(LsaError::InvalidArgument(format!(
+                        "invalid background \"{background}\", expected \"#rrggbb\""
+                    )));
+                };
+                let name = name.clone().unwrap_or_else(|| load_config().default_theme);
+                let theme = get_theme_by_name(&name).ok_or(LsaError::ThemeNotFound(name))?;
+
+                if *fix {
+                    let fixed = theme_lint::fix_theme(&theme, background);
+                    print!("{}", user_themes::theme_to_toml(&fixed));
+                    return Ok(());
+                }
+
+                let report = theme_lint::lint_theme(&theme, background);
+                show_theme_lint(&theme, &report, cli.format);
+                if report.has_problems() {
+                    error::report_minor_problem();
+                }
+            }
+            ThemeCommand::Gallery { .. } => show_theme_gallery(get_themes(), cli.format),
+        }
+        return Ok(());
     }
 
     if let Some(theme_option) = &cli.theme {
@@ -89,106 +384,145 @@ fn main() {
 
         let selected_theme_name = match theme_option {
             Some(theme_name) => theme_name.clone(),
-            None => {
-                let theme_options: Vec<String> = themes
-                    .iter()
-                    .map(|t| format!("{} - {}", t.name, t.description))
-                    .collect();
-
-                match Select::new("Select a theme to set as default:", theme_options).prompt() {
-                    Ok(selection) => {
-                        let theme_name = selection.split(" - ").next().unwrap();
-                        theme_name.to_string()
-                    }
-                    Err(_) => {
-                        println!("Theme selection cancelled.");
-                        return;
-                    }
+            None => match pick_theme(themes, &load_config().default_theme) {
+                Ok(Some(theme_name)) => theme_name,
+                Ok(None) => {
+                    println!("Theme selection cancelled.");
+                    return Ok(());
                 }
-            }
+                Err(e) => return Err(LsaError::Theme(e.to_string())),
+            },
         };
 
-        if let Some(theme) = themes.iter().find(|t| t.name == selected_theme_name) {
-            let config = Config {
-                default_theme: theme.name.clone(),
-            };
-            match save_config(&config) {
-                Ok(_) => {
-                    println!("✓ Set '{}' as default theme", theme.name);
-                    return;
-                }
-                Err(e) => {
-                    eprintln!("Error saving config: {e}");
-                    return;
-                }
-            }
-        } else {
-            println!(
-                "Theme '{}' not found. Available themes:",
-                selected_theme_name
-            );
+        let Some(theme) = themes.iter().find(|t| t.name == selected_theme_name) else {
+            eprintln!("Available themes:");
             for theme in themes {
-                println!("  {} - {}", theme.name, theme.description);
+                eprintln!("  {} - {}", theme.name, theme.description);
             }
-            return;
-        }
+            return Err(LsaError::ThemeNotFound(selected_theme_name));
+        };
+
+        let config = Config {
+            default_theme: theme.name.clone(),
+            ..load_config()
+        };
+        save_config(&config)?;
+        println!("✓ Set '{}' as default theme", theme.name);
+        return Ok(());
     }
 
     if cli.cpu {
-        let config = load_config();
-        let theme =
-            get_theme_by_name(&config.default_theme).unwrap_or_else(|| get_themes()[0].clone());
-        show_cpu_info(&theme);
-        return;
+        return show_cpu_info(&default_theme(), cli.format);
     }
 
     if let Some(file_path) = &cli.workspace_file {
-        if let Err(e) = workspace::copy_file_to_clipboard(file_path) {
-            eprintln!("Error copying file: {e}");
-        }
-        return;
+        return workspace::copy_file_to_clipboard(file_path);
     }
 
     if let Some(folder_path) = &cli.workspace_folder {
-        if let Err(e) = workspace::copy_folder_to_clipboard(folder_path) {
-            eprintln!("Error copying folder: {e}");
-        }
-        return;
+        return workspace::copy_folder_to_clipboard(folder_path);
     }
 
     if cli.workspace {
-        if let Err(e) = workspace::print_workspace_snapshot(cli.source_only, cli.max_size) {
-            eprintln!("Error printing workspace: {e}");
-        }
-        return;
+        return workspace::print_workspace_snapshot(cli.source_only, cli.max_size);
+    }
+
+    let query = match &cli.query {
+        Some(source) => Some(source.parse::<query::Query>().map_err(|e| {
+            LsaError::InvalidArgument(format!("invalid --where expression: {e}"))
+        })?),
+        None => None,
+    };
+    let filter = EntryFilter {
+        names: cli.name.clone(),
+        regex: cli.regex.clone(),
+        types: cli.types.clone(),
+        min_size: cli.min_size,
+        max_size: cli.max_entry_size,
+        query,
+        ..Default::default()
+    }
+    .with_ages(cli.newer, cli.older);
+    let sort = SortOptions {
+        keys: cli.sort.clone(),
+        reverse: cli.reverse,
+        natural: cli.natural,
+        ignore_case: cli.ignore_case,
+    };
+
+    let hidden = if cli.all {
+        HiddenFiles::All
+    } else if cli.almost_all {
+        HiddenFiles::AlmostAll
+    } else {
+        HiddenFiles::Hide
+    };
+
+    let traversal = Traversal {
+        follow: cli.follow,
+        one_file_system: cli.one_file_system,
+    };
+
+    if cli.dir_sizes || cli.sizes {
+        dirsize::install_interrupt_handler();
     }
 
     if cli.tree {
-        let config = load_config();
-        let theme =
-            get_theme_by_name(&config.default_theme).unwrap_or_else(|| get_themes()[0].clone());
-        show_tree(&theme, cli.depth, cli.all);
-        return;
+        let options = TreeOptions {
+            annotations: TreeAnnotations {
+                sizes: cli.sizes,
+                counts: cli.counts,
+                max_entries: cli.max_entries,
+            },
+            max_depth: cli.depth,
+            hidden,
+            git_ignore: cli.git_ignore,
+            git: !cli.no_git,
+            sort: &sort,
+            filter: &filter,
+            patterns: cli.patterns.clone(),
+            prune: cli.prune,
+            dirs_only: cli.dirs_only,
+            collapse: collapsed_directories(&cli.expand),
+            traversal,
+        };
+        let mut roots = expand_paths(&cli.paths);
+        if cli.paths.is_empty() {
+            roots.push(PathBuf::from("."));
+        }
+        return show_tree(&default_theme(), &roots, &options, cli.format);
     }
 
     if cli.path {
-        let config = load_config();
-        let theme =
-            get_theme_by_name(&config.default_theme).unwrap_or_else(|| get_themes()[0].clone());
-        show_path_table(&theme);
-        return;
+        return show_path_table(&default_theme(), cli.format);
     }
 
     if let Some(file_path) = &cli.open {
-        let config = load_config();
-        let theme =
-            get_theme_by_name(&config.default_theme).unwrap_or_else(|| get_themes()[0].clone());
-        show_structured_data(&theme, file_path);
-        return;
+        return show_structured_data(&default_theme(), file_path, cli.format);
     }
 
-    let config = load_config();
-    let theme = get_theme_by_name(&config.default_theme).unwrap_or_else(|| get_themes()[0].clone());
-    show_directory_table(&theme, cli.directory.as_deref(), cli.sort.as_ref());
+    let columns = if cli.columns.is_empty() {
+        configured_columns()
+    } else {
+        cli.columns.clone()
+    };
+    let options = ListOptions {
+        sort: &sort,
+        dir_sizes: cli.dir_sizes,
+        dereference: cli.dereference,
+        git: !cli.no_git,
+        filter: &filter,
+        columns: &columns,
+        grid: cli.grid,
+        hidden,
+        recursive: cli.recursive,
+        git_ignore: cli.git_ignore,
+        limit: cli.limit,
+        traversal,
+    };
+    let mut paths = expand_paths(&cli.paths);
+    if cli.paths.is_empty() {
+        paths.push(env::current_dir().map_err(LsaError::CurrentDir)?);
+    }
+    show_listing(&default_theme(), &paths, &options, cli.format)
 }
-
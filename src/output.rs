@@ -0,0 +1,160 @@
+use clap::ValueEnum;
+use serde_json::{Map, Value};
+use std::io::{self, Write};
+
+/// How a view is written to stdout. Everything except `Table` is meant for
+/// scripts: no colors, no icons, one record per entry with a fixed set of
+/// columns per view.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    #[default]
+    Table,
+    Json,
+    Ndjson,
+    Csv,
+    Tsv,
+}
+
+impl OutputFormat {
+    pub fn is_table(&self) -> bool {
+        *self == OutputFormat::Table
+    }
+}
+
+/// Rows of a view in a fixed column order. Every row has exactly one value
+/// per column, so the schema does not depend on the data.
+pub struct Records {
+    columns: Vec<&'static str>,
+    rows: Vec<Vec<Value>>,
+}
+
+impl Records {
+    pub fn new(columns: &[&'static str]) -> Self {
+        Records {
+            columns: columns.to_vec(),
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, row: Vec<Value>) {
+        debug_assert_eq!(row.len(), self.columns.len());
+        self.rows.push(row);
+    }
+
+    fn objects(&self) -> impl Iterator<Item = Value> + '_ {
+        self.rows.iter().map(|row| {
+            let object: Map<String, Value> = self
+                .columns
+                .iter()
+                .map(|c| c.to_string())
+                .zip(row.iter().cloned())
+                .collect();
+            Value::Object(object)
+        })
+    }
+
+    pub fn render(&self, format: OutputFormat) -> String {
+        match format {
+            OutputFormat::Table | OutputFormat::Json => {
+                let array = Value::Array(self.objects().collect());
+                serde_json::to_string_pretty(&array).unwrap_or_default()
+            }
+            OutputFormat::Ndjson => self
+                .objects()
+                .map(|object| object.to_string())
+                .collect::<Vec<_>>()
+                .join("\n"),
+            OutputFormat::Csv => self.render_delimited(',', csv_field),
+            OutputFormat::Tsv => self.render_delimited('\t', tsv_field),
+        }
+    }
+
+    fn render_delimited(&self, separator: char, field: fn(&str) -> String) -> String {
+        let separator = separator.to_string();
+        let mut lines = vec![
+            self.columns
+                .iter()
+                .map(|c| field(c))
+                .collect::<Vec<_>>()
+                .join(&separator),
+        ];
+        for row in &self.rows {
+            lines.push(
+                row.iter()
+                    .map(|value| field(&plain_value(value)))
+                    .collect::<Vec<_>>()
+                    .join(&separator),
+            );
+        }
+        lines.join("\n")
+    }
+}
+
+/// Writes the records to stdout. Output is usually piped into other tools, so
+/// a closed pipe (`lsa --format csv | head`) is not treated as an error.
+pub fn print_records(records: &Records, format: OutputFormat) {
+    let output = records.render(format);
+    if !output.is_empty() {
+        let _ = writeln!(io::stdout().lock(), "{output}");
+    }
+}
+
+fn plain_value(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn tsv_field(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OutputFormat, Records};
+    use serde_json::json;
+
+    fn sample() -> Records {
+        let mut records = Records::new(&["name", "size"]);
+        records.push(vec![json!("a, \"b\""), json!(10)]);
+        records.push(vec![json!("tab\there"), json!(null)]);
+        records
+    }
+
+    #[test]
+    fn test_csv_quotes_fields() {
+        assert_eq!(
+            sample().render(OutputFormat::Csv),
+            "name,size\n\"a, \"\"b\"\"\",10\ntab\there,"
+        );
+    }
+
+    #[test]
+    fn test_tsv_escapes_tabs() {
+        assert_eq!(
+            sample().render(OutputFormat::Tsv),
+            "name\tsize\na, \"b\"\t10\ntab\\there\t"
+        );
+    }
+
+    #[test]
+    fn test_ndjson_keeps_column_order() {
+        let output = sample().render(OutputFormat::Ndjson);
+        let first = output.lines().next().unwrap();
+        assert_eq!(first, r#"{"name":"a, \"b\"","size":10}"#);
+    }
+}
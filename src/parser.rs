@@ -5,6 +5,8 @@ use std::fs;
 use std::path::Path;
 use toml::Value as TomlValue;
 
+use crate::error::LsaError;
+
 #[derive(Debug, Clone)]
 pub enum DataValue {
     String(String),
@@ -44,9 +46,13 @@ pub struct ParsedData {
     pub format: String,
 }
 
-pub fn parse_file(file_path: &str) -> Result<ParsedData, Box<dyn std::error::Error>> {
+pub fn parse_file(file_path: &str) -> Result<ParsedData, LsaError> {
     let path = Path::new(file_path);
-    let content = fs::read_to_string(path)?;
+    let content = fs::read_to_string(path).map_err(|e| LsaError::io(path, e))?;
+    let parse_error = |message: String| LsaError::Parse {
+        path: path.to_path_buf(),
+        message,
+    };
     
     let extension = path
         .extension()
@@ -56,27 +62,33 @@ pub fn parse_file(file_path: &str) -> Result<ParsedData, Box<dyn std::error::Err
 
     match extension.as_str() {
         "json" => {
-            let json_value: JsonValue = serde_json::from_str(&content)?;
+            let json_value: JsonValue =
+                serde_json::from_str(&content).map_err(|e| parse_error(e.to_string()))?;
             Ok(ParsedData {
                 data: json_to_data_value(json_value),
                 format: "JSON".to_string(),
             })
         }
         "yaml" | "yml" => {
-            let yaml_value: YamlValue = serde_yaml::from_str(&content)?;
+            let yaml_value: YamlValue =
+                serde_yaml::from_str(&content).map_err(|e| parse_error(e.to_string()))?;
             Ok(ParsedData {
                 data: yaml_to_data_value(yaml_value),
                 format: "YAML".to_string(),
             })
         }
         "toml" => {
-            let toml_value: TomlValue = toml::from_str(&content)?;
+            let toml_value: TomlValue =
+                toml::from_str(&content).map_err(|e| parse_error(e.to_string()))?;
             Ok(ParsedData {
                 data: toml_to_data_value(toml_value),
                 format: "TOML".to_string(),
             })
         }
-        _ => Err(format!("Unsupported file format: {}", extension).into()),
+        _ => Err(LsaError::UnsupportedFormat {
+            path: path.to_path_buf(),
+            extension,
+        }),
     }
 }
 
@@ -0,0 +1,128 @@
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use globset::GlobBuilder;
+use ignore::WalkBuilder;
+
+use crate::error::LsaError;
+use crate::sort::natural_cmp;
+
+/// Whether `arg` contains glob syntax.
+pub fn is_glob(arg: &str) -> bool {
+    arg.contains(['*', '?', '[', '{'])
+}
+
+/// Resolves one path from the command line. Globs are expanded here so they
+/// also work where the shell does not do it, e.g. on Windows or when quoted.
+/// A path that exists is taken literally even if it looks like a glob.
+pub fn expand_path(arg: &str) -> Result<Vec<PathBuf>, LsaError> {
+    let path = Path::new(arg);
+    let missing = match fs::symlink_metadata(path) {
+        Ok(_) => return Ok(vec![path.to_path_buf()]),
+        Err(e) => e,
+    };
+    if !is_glob(arg) {
+        return Err(LsaError::io(path, missing));
+    }
+
+    let matches = expand_glob(arg)?;
+    if matches.is_empty() {
+        return Err(LsaError::io(path, io::ErrorKind::NotFound.into()));
+    }
+    Ok(matches)
+}
+
+/// Paths matching `pattern`, in natural order. Like a shell, `*` stays within
+/// one path component, `**` crosses them, and hidden entries only match
+/// when the pattern asks for them with a leading dot.
+fn expand_glob(pattern: &str) -> Result<Vec<PathBuf>, LsaError> {
+    // Candidates are matched with `/` separators on every platform
+    let normalized = if cfg!(windows) {
+        pattern.replace('\\', "/")
+    } else {
+        pattern.to_string()
+    };
+    let matcher = GlobBuilder::new(&normalized)
+        .literal_separator(true)
+        .build()
+        .map_err(|e| LsaError::InvalidArgument(format!("invalid glob '{pattern}': {e}")))?
+        .compile_matcher();
+
+    // Walk from the longest leading part without glob syntax. Components keep
+    // roots and drive prefixes like `/` and `C:\` in the base.
+    let components: Vec<Component> = Path::new(pattern).components().collect();
+    let literal = components
+        .iter()
+        .take_while(|c| !is_glob(&c.as_os_str().to_string_lossy()))
+        .count();
+    let base: PathBuf = match components[..literal].iter().collect::<PathBuf>() {
+        base if base.as_os_str().is_empty() => PathBuf::from("."),
+        base => base,
+    };
+    let rest: Vec<String> = components[literal..]
+        .iter()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+    let max_depth = (!rest.iter().any(|c| c == "**")).then_some(rest.len());
+    let hidden = rest.iter().any(|c| c.starts_with('.'));
+
+    let mut matches: Vec<PathBuf> = WalkBuilder::new(&base)
+        .standard_filters(false)
+        .hidden(!hidden)
+        .max_depth(max_depth)
+        .build()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.depth() > 0)
+        .map(|entry| {
+            let path = entry.into_path();
+            // Patterns without a directory part match `x`, not `./x`
+            match path.strip_prefix(".") {
+                Ok(relative) if base == Path::new(".") => relative.to_path_buf(),
+                _ => path,
+            }
+        })
+        .filter(|path| matcher.is_match(path))
+        .collect();
+    matches.sort_by(|a, b| natural_cmp(&a.to_string_lossy(), &b.to_string_lossy(), false));
+    Ok(matches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::expand_path;
+    use assert_fs::TempDir;
+    use assert_fs::prelude::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_expand_globs_and_literal_paths() {
+        let dir = TempDir::new().unwrap();
+        for file in ["src/main.rs", "src/lib.rs", "src/bin/tool.rs", "src/.hidden.rs", "notes[1].md"] {
+            dir.child(file).write_str("").unwrap();
+        }
+        let root = dir.path().to_string_lossy().to_string();
+        let relative = |paths: Vec<PathBuf>| -> Vec<String> {
+            paths
+                .iter()
+                .map(|p| p.strip_prefix(dir.path()).unwrap().to_string_lossy().to_string())
+                .collect()
+        };
+
+        let found = expand_path(&format!("{root}/src/*.rs")).unwrap();
+        assert_eq!(relative(found), ["src/lib.rs", "src/main.rs"]);
+
+        let found = expand_path(&format!("{root}/src/**/*.rs")).unwrap();
+        assert_eq!(relative(found), ["src/bin/tool.rs", "src/lib.rs", "src/main.rs"]);
+
+        let found = expand_path(&format!("{root}/src/.*.rs")).unwrap();
+        assert_eq!(relative(found), ["src/.hidden.rs"]);
+
+        // Existing paths win over glob syntax
+        let found = expand_path(&format!("{root}/notes[1].md")).unwrap();
+        assert_eq!(relative(found), ["notes[1].md"]);
+
+        let error = expand_path(&format!("{root}/src/*.py")).unwrap_err();
+        assert!(error.to_string().ends_with("no such file or directory"));
+    }
+}
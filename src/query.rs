@@ -0,0 +1,689 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
+
+use clap::ValueEnum;
+use globset::GlobMatcher;
+use regex::Regex;
+
+use crate::filter::{EntryInfo, EntryType, parse_age, parse_glob, parse_size};
+
+const FIELDS: &str = "name, ext, type, size, modified, age, mode, permissions";
+const FLAGS: &str = "hidden, file, dir, symlink, executable";
+
+/// A parsed `--where` expression, e.g.
+/// `size > 1M and ext in (rs, toml) and modified < 7d and not hidden`.
+///
+/// ```text
+/// expr       := and ("or" and)*
+/// and        := unary ("and" unary)*
+/// unary      := "not" unary | "(" expr ")" | flag | comparison
+/// comparison := field op value | field "in" "(" value ("," value)* ")"
+/// op         := = != < <= > >= ~ (glob) =~ (regex)
+/// ```
+#[derive(Debug)]
+pub struct Query {
+    expr: Expr,
+    // Ages such as `7d` are measured from the moment the query was parsed
+    now: SystemTime,
+}
+
+impl Query {
+    pub fn matches(&self, entry: &EntryInfo) -> bool {
+        self.expr.eval(entry, self.now)
+    }
+}
+
+impl FromStr for Query {
+    type Err = QueryError;
+
+    fn from_str(source: &str) -> Result<Query, QueryError> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser {
+            source,
+            tokens,
+            position: 0,
+        };
+        let expr = parser.expr()?;
+        if let Some(token) = parser.peek() {
+            return Err(parser.error_at(
+                token,
+                format!("unexpected {}, expected \"and\" or \"or\"", token.describe()),
+            ));
+        }
+        Ok(Query {
+            expr,
+            now: SystemTime::now(),
+        })
+    }
+}
+
+/// A parse error with the offending part of the expression underlined.
+#[derive(Debug)]
+pub struct QueryError {
+    message: String,
+    source: String,
+    // Character offsets, so the marker lines up with the echoed expression
+    start: usize,
+    len: usize,
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.message)?;
+        writeln!(f, "  {}", self.source)?;
+        write!(f, "  {}{}", " ".repeat(self.start), "^".repeat(self.len.max(1)))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Field {
+    Name,
+    Ext,
+    Type,
+    Size,
+    Modified,
+    // Time since the last modification, so `age < 7d` is the recent side
+    Age,
+    Mode,
+    Permissions,
+}
+
+impl Field {
+    fn parse(word: &str) -> Option<Field> {
+        Some(match word.to_lowercase().as_str() {
+            "name" => Field::Name,
+            "ext" | "extension" => Field::Ext,
+            "type" => Field::Type,
+            "size" => Field::Size,
+            "modified" | "mtime" => Field::Modified,
+            "age" => Field::Age,
+            "mode" => Field::Mode,
+            "permissions" | "perms" => Field::Permissions,
+            _ => return None,
+        })
+    }
+
+    fn is_text(&self) -> bool {
+        matches!(self, Field::Name | Field::Ext | Field::Permissions)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Glob,
+    Regex,
+}
+
+impl Op {
+    fn holds(&self, ordering: Ordering) -> bool {
+        match self {
+            Op::Eq => ordering == Ordering::Equal,
+            Op::Ne => ordering != Ordering::Equal,
+            Op::Lt => ordering == Ordering::Less,
+            Op::Le => ordering != Ordering::Greater,
+            Op::Gt => ordering == Ordering::Greater,
+            Op::Ge => ordering != Ordering::Less,
+            Op::Glob | Op::Regex => false,
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Test {
+    Equals(String),
+    Glob(GlobMatcher),
+    Regex(Regex),
+    Type(EntryType),
+    Number(Op, u64),
+    // Time since the last modification
+    Age(Op, Duration),
+    // Modification time against a date
+    Time(Op, SystemTime),
+}
+
+#[derive(Debug)]
+enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Hidden,
+    Test(Field, Test),
+}
+
+impl Expr {
+    fn eval(&self, entry: &EntryInfo, now: SystemTime) -> bool {
+        match self {
+            Expr::And(a, b) => a.eval(entry, now) && b.eval(entry, now),
+            Expr::Or(a, b) => a.eval(entry, now) || b.eval(entry, now),
+            Expr::Not(expr) => !expr.eval(entry, now),
+            Expr::Hidden => entry.name.starts_with('.'),
+            Expr::Test(field, test) => test.eval(*field, entry, now),
+        }
+    }
+}
+
+impl Test {
+    /// Unknown values (e.g. the size of a directory without `--dir-sizes`)
+    /// never match.
+    fn eval(&self, field: Field, entry: &EntryInfo, now: SystemTime) -> bool {
+        let text = || match field {
+            Field::Name => Some(entry.name.to_string()),
+            Field::Ext => Path::new(entry.name)
+                .extension()
+                .map(|ext| ext.to_string_lossy().to_lowercase()),
+            Field::Permissions => permissions_text(entry.mode),
+            _ => None,
+        };
+
+        match self {
+            Test::Equals(value) => text().is_some_and(|text| &text == value),
+            Test::Glob(glob) => text().is_some_and(|text| glob.is_match(text)),
+            Test::Regex(regex) => text().is_some_and(|text| regex.is_match(&text)),
+            Test::Type(entry_type) => entry.kind.is_some_and(|kind| entry_type.matches(kind)),
+            Test::Number(op, value) => {
+                let actual = match field {
+                    Field::Size => entry.size,
+                    Field::Mode => entry.mode.map(|mode| (mode & 0o7777) as u64),
+                    _ => None,
+                };
+                actual.is_some_and(|actual| op.holds(actual.cmp(value)))
+            }
+            Test::Age(op, age) => entry
+                .modified
+                .is_some_and(|modified| op.holds(age_of(modified, now).cmp(age))),
+            Test::Time(op, time) => entry
+                .modified
+                .is_some_and(|modified| op.holds(modified.cmp(time))),
+        }
+    }
+}
+
+#[cfg(unix)]
+fn permissions_text(mode: Option<u32>) -> Option<String> {
+    mode.map(crate::utils::format_permissions)
+}
+
+#[cfg(not(unix))]
+fn permissions_text(_mode: Option<u32>) -> Option<String> {
+    None
+}
+
+fn age_of(modified: SystemTime, now: SystemTime) -> Duration {
+    now.duration_since(modified).unwrap_or_default()
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum TokenKind {
+    LParen,
+    RParen,
+    Comma,
+    Op(Op),
+    Word(String),
+    // Quoted with ' or ", never a keyword
+    Quoted(String),
+}
+
+#[derive(Clone, Debug)]
+struct Token {
+    kind: TokenKind,
+    start: usize,
+    end: usize,
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match &self.kind {
+            TokenKind::LParen => "\"(\"".to_string(),
+            TokenKind::RParen => "\")\"".to_string(),
+            TokenKind::Comma => "\",\"".to_string(),
+            TokenKind::Op(_) => "an operator".to_string(),
+            TokenKind::Word(word) | TokenKind::Quoted(word) => format!("\"{word}\""),
+        }
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(&self.kind, TokenKind::Word(word) if word.eq_ignore_ascii_case(keyword))
+    }
+
+    fn value(&self) -> Option<&str> {
+        match &self.kind {
+            TokenKind::Word(word) | TokenKind::Quoted(word) => Some(word),
+            _ => None,
+        }
+    }
+}
+
+fn error(source: &str, start: usize, end: usize, message: String) -> QueryError {
+    QueryError {
+        message,
+        source: source.to_string(),
+        start: source[..start].chars().count(),
+        len: source[start..end].chars().count(),
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, QueryError> {
+    let mut tokens = Vec::new();
+    let mut chars = source.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+
+        let single = match c {
+            '(' => Some(TokenKind::LParen),
+            ')' => Some(TokenKind::RParen),
+            ',' => Some(TokenKind::Comma),
+            _ => None,
+        };
+        if let Some(kind) = single {
+            chars.next();
+            tokens.push(Token {
+                kind,
+                start,
+                end: start + 1,
+            });
+            continue;
+        }
+
+        if c == '\'' || c == '"' {
+            chars.next();
+            let mut value = String::new();
+            let mut end = None;
+            for (i, ch) in chars.by_ref() {
+                if ch == c {
+                    end = Some(i + 1);
+                    break;
+                }
+                value.push(ch);
+            }
+            let Some(end) = end else {
+                return Err(error(
+                    source,
+                    start,
+                    source.len(),
+                    "unterminated quoted value".to_string(),
+                ));
+            };
+            tokens.push(Token {
+                kind: TokenKind::Quoted(value),
+                start,
+                end,
+            });
+            continue;
+        }
+
+        if "<>=!~".contains(c) {
+            let mut op = String::new();
+            while let Some(&(_, ch)) = chars.peek() {
+                if !"<>=!~".contains(ch) {
+                    break;
+                }
+                op.push(ch);
+                chars.next();
+            }
+            let end = start + op.len();
+            let kind = match op.as_str() {
+                "=" | "==" => Op::Eq,
+                "!=" => Op::Ne,
+                "<" => Op::Lt,
+                "<=" => Op::Le,
+                ">" => Op::Gt,
+                ">=" => Op::Ge,
+                "~" => Op::Glob,
+                "=~" => Op::Regex,
+                _ => {
+                    return Err(error(
+                        source,
+                        start,
+                        end,
+                        format!("unknown operator \"{op}\", expected = != < <= > >= ~ or =~"),
+                    ));
+                }
+            };
+            tokens.push(Token {
+                kind: TokenKind::Op(kind),
+                start,
+                end,
+            });
+            continue;
+        }
+
+        let mut word = String::new();
+        let mut end = start;
+        while let Some(&(i, ch)) = chars.peek() {
+            if ch.is_whitespace() || "(),<>=!~'\"".contains(ch) {
+                break;
+            }
+            word.push(ch);
+            end = i + ch.len_utf8();
+            chars.next();
+        }
+        tokens.push(Token {
+            kind: TokenKind::Word(word),
+            start,
+            end,
+        });
+    }
+
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self, expected: &str) -> Result<Token, QueryError> {
+        match self.tokens.get(self.position) {
+            Some(token) => {
+                self.position += 1;
+                Ok(token.clone())
+            }
+            None => Err(error(
+                self.source,
+                self.source.len(),
+                self.source.len(),
+                format!("expected {expected} but the expression ended"),
+            )),
+        }
+    }
+
+    fn error_at(&self, token: &Token, message: String) -> QueryError {
+        error(self.source, token.start, token.end, message)
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let found = self.peek().is_some_and(|t| t.is_keyword(keyword));
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    fn expr(&mut self) -> Result<Expr, QueryError> {
+        let mut expr = self.and()?;
+        while self.eat_keyword("or") {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, QueryError> {
+        let mut expr = self.unary()?;
+        while self.eat_keyword("and") {
+            expr = Expr::And(Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr, QueryError> {
+        if self.eat_keyword("not") {
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+
+        let token = self.next("a condition")?;
+        match &token.kind {
+            TokenKind::LParen => {
+                let expr = self.expr()?;
+                let close = self.next("\")\"")?;
+                if close.kind != TokenKind::RParen {
+                    return Err(self.error_at(
+                        &close,
+                        format!("expected \")\" but found {}", close.describe()),
+                    ));
+                }
+                Ok(expr)
+            }
+            TokenKind::Word(word) => {
+                if let Some(field) = Field::parse(word) {
+                    return self.comparison(field);
+                }
+                match word.to_lowercase().as_str() {
+                    "hidden" => Ok(Expr::Hidden),
+                    "file" => Ok(Expr::Test(Field::Type, Test::Type(EntryType::File))),
+                    "dir" => Ok(Expr::Test(Field::Type, Test::Type(EntryType::Dir))),
+                    "symlink" => Ok(Expr::Test(Field::Type, Test::Type(EntryType::Symlink))),
+                    "executable" => {
+                        Ok(Expr::Test(Field::Type, Test::Type(EntryType::Executable)))
+                    }
+                    _ => Err(self.error_at(
+                        &token,
+                        format!(
+                            "unknown field \"{word}\", expected one of {FIELDS} or a flag ({FLAGS})"
+                        ),
+                    )),
+                }
+            }
+            _ => Err(self.error_at(
+                &token,
+                format!("expected a condition but found {}", token.describe()),
+            )),
+        }
+    }
+
+    fn comparison(&mut self, field: Field) -> Result<Expr, QueryError> {
+        if self.eat_keyword("in") {
+            return self.in_list(field);
+        }
+
+        let token = self.next("an operator")?;
+        let TokenKind::Op(op) = token.kind else {
+            return Err(self.error_at(
+                &token,
+                format!("expected an operator or \"in\" but found {}", token.describe()),
+            ));
+        };
+        let value = self.next("a value")?;
+
+        match op {
+            Op::Glob | Op::Regex if !field.is_text() => Err(self.error_at(
+                &token,
+                "~ and =~ only work on name, ext and permissions".to_string(),
+            )),
+            Op::Lt | Op::Le | Op::Gt | Op::Ge
+                if field.is_text() || field == Field::Type =>
+            {
+                Err(self.error_at(
+                    &token,
+                    "<, <=, > and >= only work on size, modified, age and mode".to_string(),
+                ))
+            }
+            Op::Ne if field.is_text() || field == Field::Type => {
+                Ok(Expr::Not(Box::new(self.test(field, Op::Eq, &value)?)))
+            }
+            _ => self.test(field, op, &value),
+        }
+    }
+
+    fn in_list(&mut self, field: Field) -> Result<Expr, QueryError> {
+        let open = self.next("\"(\"")?;
+        if open.kind != TokenKind::LParen {
+            return Err(self.error_at(
+                &open,
+                format!("expected \"(\" after \"in\" but found {}", open.describe()),
+            ));
+        }
+
+        let mut expr: Option<Expr> = None;
+        loop {
+            let value = self.next("a value")?;
+            let test = self.test(field, Op::Eq, &value)?;
+            expr = Some(match expr {
+                Some(expr) => Expr::Or(Box::new(expr), Box::new(test)),
+                None => test,
+            });
+
+            let separator = self.next("\",\" or \")\"")?;
+            match separator.kind {
+                TokenKind::Comma => continue,
+                TokenKind::RParen => break,
+                _ => {
+                    return Err(self.error_at(
+                        &separator,
+                        format!("expected \",\" or \")\" but found {}", separator.describe()),
+                    ));
+                }
+            }
+        }
+        Ok(expr.expect("the loop reads at least one value"))
+    }
+
+    fn test(&self, field: Field, op: Op, token: &Token) -> Result<Expr, QueryError> {
+        let Some(value) = token.value() else {
+            return Err(self.error_at(
+                token,
+                format!("expected a value but found {}", token.describe()),
+            ));
+        };
+        let invalid = |message: String| self.error_at(token, message);
+
+        let test = match (field, op) {
+            (_, Op::Glob) => Test::Glob(parse_glob(value).map_err(invalid)?),
+            (_, Op::Regex) => {
+                Test::Regex(Regex::new(value).map_err(|e| invalid(e.to_string()))?)
+            }
+            (Field::Ext, _) => {
+                Test::Equals(value.trim_start_matches('.').to_lowercase())
+            }
+            (Field::Name | Field::Permissions, _) => Test::Equals(value.to_string()),
+            (Field::Type, _) => Test::Type(EntryType::from_str(value, true).map_err(|_| {
+                invalid(format!(
+                    "unknown type \"{value}\", expected file, dir, symlink or executable"
+                ))
+            })?),
+            (Field::Size, _) => Test::Number(op, parse_size(value).map_err(invalid)?),
+            (Field::Mode, _) => Test::Number(
+                op,
+                u64::from_str_radix(value, 8).map_err(|_| {
+                    invalid(format!("invalid mode \"{value}\", expected octal such as 755"))
+                })?,
+            ),
+            (Field::Age, _) => Test::Age(
+                op,
+                parse_age(value).map_err(|e| {
+                    invalid(format!("{e}, dates go with modified, e.g. modified < 2024-01-31"))
+                })?,
+            ),
+            // A date compares times, so `< 2024-01-01` is older. An age such
+            // as `7d` reads like `age`, so `< 7d` is newer.
+            (Field::Modified, _) => match parse_date(value) {
+                Some(time) => Test::Time(op, time),
+                None => Test::Age(
+                    op,
+                    parse_age(value).map_err(|e| {
+                        invalid(format!("{e}, or a date such as 2024-01-31"))
+                    })?,
+                ),
+            },
+        };
+        Ok(Expr::Test(field, test))
+    }
+}
+
+/// Midnight local time of a `YYYY-MM-DD` date.
+fn parse_date(value: &str) -> Option<SystemTime> {
+    let date = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()?;
+    let local = date.and_hms_opt(0, 0, 0)?.and_local_timezone(chrono::Local);
+    Some(local.earliest()?.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Query;
+    use crate::file_kind::FileKind;
+    use crate::filter::EntryInfo;
+    use std::time::{Duration, SystemTime};
+
+    fn entry(name: &str, kind: FileKind, size: u64, age_days: u64) -> EntryInfo<'_> {
+        EntryInfo {
+            name,
+            kind: Some(kind),
+            size: Some(size),
+            modified: SystemTime::now().checked_sub(Duration::from_secs(age_days * 86400)),
+            mode: Some(0o644),
+        }
+    }
+
+    #[test]
+    fn test_query_from_request() {
+        let query: Query = "size > 1M and ext in (rs, toml) and modified < 7d and not hidden"
+            .parse()
+            .unwrap();
+
+        assert!(query.matches(&entry("main.rs", FileKind::File, 2 << 20, 1)));
+        assert!(query.matches(&entry("Cargo.TOML", FileKind::File, 2 << 20, 1)));
+        assert!(!query.matches(&entry("main.rs", FileKind::File, 1000, 1)));
+        assert!(!query.matches(&entry("main.rs", FileKind::File, 2 << 20, 30)));
+        assert!(!query.matches(&entry(".hidden.rs", FileKind::File, 2 << 20, 1)));
+        assert!(!query.matches(&entry("notes.md", FileKind::File, 2 << 20, 1)));
+    }
+
+    #[test]
+    fn test_precedence_and_operators() {
+        let query: Query = "dir or name ~ '*.md' and not (mode = 644 or perms =~ '^r')"
+            .parse()
+            .unwrap();
+        // `and` binds tighter than `or`
+        assert!(query.matches(&entry("src", FileKind::Directory, 0, 1)));
+        assert!(!query.matches(&entry("README.md", FileKind::File, 10, 1)));
+
+        let query: Query = "type != dir and modified > 2000-01-01".parse().unwrap();
+        assert!(query.matches(&entry("a", FileKind::File, 0, 1)));
+        assert!(!query.matches(&entry("a", FileKind::Directory, 0, 1)));
+    }
+
+    #[test]
+    fn test_ages_and_dates_point_opposite_ways() {
+        let week_ago = chrono::Local::now() - chrono::Duration::days(7);
+        let matches = |source: &str| {
+            let query: Query = source.parse().unwrap();
+            (
+                query.matches(&entry("new", FileKind::File, 0, 1)),
+                query.matches(&entry("old", FileKind::File, 0, 30)),
+            )
+        };
+        assert_eq!(matches("age < 7d"), (true, false));
+        assert_eq!(matches("modified < 7d"), (true, false));
+        assert_eq!(matches(&format!("modified < {}", week_ago.format("%Y-%m-%d"))), (false, true));
+        assert_eq!(matches("age > 7d"), (false, true));
+    }
+
+    #[test]
+    fn test_errors_point_at_the_bad_token() {
+        let error = "size > 1M and sise < 3".parse::<Query>().unwrap_err();
+        let message = error.to_string();
+        assert!(message.starts_with("unknown field \"sise\""), "{message}");
+        assert!(message.ends_with("\n                ^^^^"), "{message}");
+
+        let error = "size > 10Q".parse::<Query>().unwrap_err().to_string();
+        assert!(error.contains("unknown size unit"), "{error}");
+        assert!(error.ends_with("         ^^^"), "{error}");
+
+        let error = "name < foo".parse::<Query>().unwrap_err().to_string();
+        assert!(error.contains("only work on size"), "{error}");
+
+        let error = "ext in (rs, toml".parse::<Query>().unwrap_err().to_string();
+        assert!(error.contains("expression ended"), "{error}");
+
+        let error = "hidden dir".parse::<Query>().unwrap_err().to_string();
+        assert!(error.contains("expected \"and\" or \"or\""), "{error}");
+    }
+}
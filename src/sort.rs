@@ -0,0 +1,207 @@
+use std::cmp::Ordering;
+
+use clap::ValueEnum;
+
+use crate::filter::EntryInfo;
+
+/// Sort keys accepted by `--sort`. Several keys can be combined, e.g.
+/// `--sort type,size`; later keys only decide between entries that are
+/// equal on the earlier ones.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum SortBy {
+    Name,
+    Size,
+    #[value(alias = "mtime")]
+    Modified,
+    /// Directories first
+    Type,
+    #[value(alias = "ext")]
+    Extension,
+}
+
+/// Order used by the table without `--sort`.
+pub const DEFAULT_LIST_ORDER: [SortBy; 1] = [SortBy::Name];
+/// Order used by the tree without `--sort`: directories first, then by name.
+pub const DEFAULT_TREE_ORDER: [SortBy; 2] = [SortBy::Type, SortBy::Name];
+
+/// How listings order their entries.
+#[derive(Default)]
+pub struct SortOptions {
+    // Empty keeps the default order of the view
+    pub keys: Vec<SortBy>,
+    pub reverse: bool,
+    // Compare digit runs in names by their value, so `file2` < `file10`
+    pub natural: bool,
+    pub ignore_case: bool,
+}
+
+impl SortOptions {
+    /// Sorts `items` by the configured keys, or `default_keys` when none were
+    /// given. Entries equal on every key are ordered by name so the output
+    /// does not depend on the `read_dir` order.
+    pub fn sort<T>(&self, items: &mut [T], default_keys: &[SortBy], info: impl Fn(&T) -> EntryInfo<'_>) {
+        let keys = if self.keys.is_empty() {
+            default_keys
+        } else {
+            &self.keys
+        };
+        items.sort_by(|a, b| {
+            let ordering = self.compare(keys, &info(a), &info(b));
+            if self.reverse {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+    }
+
+    fn compare(&self, keys: &[SortBy], a: &EntryInfo, b: &EntryInfo) -> Ordering {
+        keys.iter()
+            .fold(Ordering::Equal, |ordering, key| {
+                ordering.then_with(|| self.compare_by(*key, a, b))
+            })
+            .then_with(|| self.compare_names(a.name, b.name))
+            .then_with(|| a.name.cmp(b.name))
+    }
+
+    fn compare_by(&self, key: SortBy, a: &EntryInfo, b: &EntryInfo) -> Ordering {
+        match key {
+            SortBy::Name => self.compare_names(a.name, b.name),
+            SortBy::Size => a.size.cmp(&b.size),
+            SortBy::Modified => a.modified.cmp(&b.modified),
+            SortBy::Type => {
+                let is_dir = |entry: &EntryInfo| entry.kind.is_some_and(|kind| kind.is_dir());
+                is_dir(b).cmp(&is_dir(a))
+            }
+            // Names without an extension come first, like `ls -X`
+            SortBy::Extension => self.compare_names(extension(a.name), extension(b.name)),
+        }
+    }
+
+    fn compare_names(&self, a: &str, b: &str) -> Ordering {
+        if self.natural {
+            natural_cmp(a, b, self.ignore_case)
+        } else if self.ignore_case {
+            a.chars()
+                .flat_map(char::to_lowercase)
+                .cmp(b.chars().flat_map(char::to_lowercase))
+        } else {
+            a.cmp(b)
+        }
+    }
+}
+
+/// The extension of `name`, empty for names without one. A leading dot marks
+/// a hidden file, not an extension. Names may be paths in recursive listings.
+fn extension(name: &str) -> &str {
+    let name = name.rsplit(['/', std::path::MAIN_SEPARATOR]).next().unwrap_or(name);
+    match name.rfind('.') {
+        Some(dot) if dot > 0 => &name[dot + 1..],
+        _ => "",
+    }
+}
+
+/// Compares names with runs of digits ordered by their value, so `file2`
+/// comes before `file10` and `v1.9` before `v1.10`. Runs with the same value
+/// are ordered by their leading zeros.
+pub fn natural_cmp(a: &str, b: &str, ignore_case: bool) -> Ordering {
+    let mut a = a.chars().peekable();
+    let mut b = b.chars().peekable();
+    loop {
+        match (a.peek().copied(), b.peek().copied()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let x = take_digits(&mut a);
+                let y = take_digits(&mut b);
+                let (x_value, y_value) = (x.trim_start_matches('0'), y.trim_start_matches('0'));
+                let ordering = x_value
+                    .len()
+                    .cmp(&y_value.len())
+                    .then_with(|| x_value.cmp(y_value))
+                    .then_with(|| y.len().cmp(&x.len()));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(x), Some(y)) => {
+                let ordering = if ignore_case {
+                    x.to_lowercase().cmp(y.to_lowercase())
+                } else {
+                    x.cmp(&y)
+                };
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                a.next();
+                b.next();
+            }
+        }
+    }
+}
+
+fn take_digits(chars: &mut std::iter::Peekable<std::str::Chars>) -> String {
+    let mut digits = String::new();
+    while let Some(c) = chars.next_if(char::is_ascii_digit) {
+        digits.push(c);
+    }
+    digits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DEFAULT_LIST_ORDER, DEFAULT_TREE_ORDER, SortBy, SortOptions, natural_cmp};
+    use crate::filter::EntryInfo;
+    use crate::file_kind::FileKind;
+    use std::cmp::Ordering;
+
+    fn sorted(options: &SortOptions, default: &[SortBy], entries: &[(&str, FileKind, u64)]) -> Vec<String> {
+        let mut entries = entries.to_vec();
+        options.sort(&mut entries, default, |(name, kind, size)| EntryInfo {
+            name,
+            kind: Some(*kind),
+            size: Some(*size),
+            modified: None,
+            mode: None,
+        });
+        entries.into_iter().map(|(name, _, _)| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_natural_cmp() {
+        assert_eq!(natural_cmp("file2", "file10", false), Ordering::Less);
+        assert_eq!(natural_cmp("v1.10", "v1.9", false), Ordering::Greater);
+        assert_eq!(natural_cmp("a01", "a1", false), Ordering::Less);
+        assert_eq!(natural_cmp("B", "a", false), Ordering::Less);
+        assert_eq!(natural_cmp("B", "a", true), Ordering::Greater);
+    }
+
+    #[test]
+    fn test_sort_keys_and_reverse() {
+        let entries = [
+            ("b.txt", FileKind::File, 10),
+            ("src", FileKind::Directory, 0),
+            ("a.rs", FileKind::File, 10),
+            ("Makefile", FileKind::File, 5),
+        ];
+
+        let default = SortOptions::default();
+        assert_eq!(sorted(&default, &DEFAULT_LIST_ORDER, &entries), ["Makefile", "a.rs", "b.txt", "src"]);
+        assert_eq!(sorted(&default, &DEFAULT_TREE_ORDER, &entries), ["src", "Makefile", "a.rs", "b.txt"]);
+
+        let by_type_size = SortOptions {
+            keys: vec![SortBy::Type, SortBy::Size],
+            reverse: true,
+            ..Default::default()
+        };
+        assert_eq!(sorted(&by_type_size, &DEFAULT_LIST_ORDER, &entries), ["b.txt", "a.rs", "Makefile", "src"]);
+
+        let by_extension = SortOptions {
+            keys: vec![SortBy::Extension],
+            ignore_case: true,
+            ..Default::default()
+        };
+        assert_eq!(sorted(&by_extension, &DEFAULT_LIST_ORDER, &entries), ["Makefile", "src", "a.rs", "b.txt"]);
+    }
+}
@@ -0,0 +1,461 @@
+use std::fs;
+use std::path::Path;
+
+use clap::ValueEnum;
+use serde_json::Value;
+
+use crate::themes::{ExtraColors, Theme};
+use crate::user_themes::parse_hex;
+
+type Rgb = (u8, u8, u8);
+
+/// Color scheme formats `lsa theme import` understands.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum SchemeFormat {
+    /// base16 or base24 scheme (YAML)
+    Base16,
+    /// Alacritty color config (TOML, or the legacy YAML)
+    Alacritty,
+    /// kitty color config (`colorN #rrggbb` lines)
+    Kitty,
+    /// iTerm2 `.itermcolors` property list
+    Iterm2,
+}
+
+impl SchemeFormat {
+    fn label(&self) -> &'static str {
+        match self {
+            SchemeFormat::Base16 => "base16 scheme",
+            SchemeFormat::Alacritty => "Alacritty config",
+            SchemeFormat::Kitty => "kitty config",
+            SchemeFormat::Iterm2 => "iTerm2 colors",
+        }
+    }
+
+    fn detect(path: &Path, content: &str) -> Option<SchemeFormat> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "itermcolors" | "plist" => Some(SchemeFormat::Iterm2),
+            "conf" => Some(SchemeFormat::Kitty),
+            "toml" => Some(SchemeFormat::Alacritty),
+            "yaml" | "yml" if content.contains("base00") => Some(SchemeFormat::Base16),
+            "yaml" | "yml" => Some(SchemeFormat::Alacritty),
+            _ => None,
+        }
+    }
+}
+
+/// The colors a theme is built from. Base16 schemes name them directly, for
+/// terminal palettes they come from the ANSI colors.
+struct Roles {
+    background: Option<Rgb>,
+    foreground: Rgb,
+    comment: Rgb,
+    red: Rgb,
+    orange: Rgb,
+    yellow: Rgb,
+    green: Rgb,
+    cyan: Rgb,
+    blue: Rgb,
+    magenta: Rgb,
+}
+
+impl Roles {
+    /// Terminal palette: background, foreground and the 16 ANSI colors.
+    fn from_ansi(
+        background: Option<Rgb>,
+        foreground: Option<Rgb>,
+        ansi: &[Option<Rgb>; 16],
+    ) -> Result<Roles, String> {
+        // Prefer the normal color and fall back to its bright variant
+        let color = |index: usize, name: &str| {
+            ansi[index]
+                .or(ansi[index + 8])
+                .ok_or_else(|| format!("palette has no {name} (color{index})"))
+        };
+        let comment = ansi[8].or(ansi[0]).ok_or("palette has no bright black (color8)")?;
+        let foreground = foreground
+            .or(ansi[7])
+            .or(ansi[15])
+            .ok_or("palette has no foreground color")?;
+
+        Ok(Roles {
+            background,
+            foreground,
+            comment,
+            red: color(1, "red")?,
+            // ANSI has no orange, so yellow stands in for it and the
+            // permissions get the bright yellow
+            orange: color(3, "yellow")?,
+            yellow: ansi[11].map_or_else(|| color(3, "yellow"), Ok)?,
+            green: color(2, "green")?,
+            cyan: color(6, "cyan")?,
+            blue: color(4, "blue")?,
+            magenta: color(5, "magenta")?,
+        })
+    }
+
+    fn into_theme(self, name: String, description: String) -> Theme {
+        // Built-in themes are tuned for dark terminals, where every slot is
+        // lifted to an average brightness of 150 (see adjust_all_colors.nu).
+        // Light schemes are kept as they are.
+        let dark = self.background.is_none_or(|bg| luminance(bg) < 0.5);
+        let adjust = |color: Rgb| if dark { brighten(color) } else { color };
+
+        Theme {
+            name,
+            description,
+            border: adjust(self.magenta),
+            header: adjust(self.red),
+            file_name: adjust(self.cyan),
+            file_type: adjust(self.cyan),
+            dir_name: adjust(self.blue),
+            dir_type: adjust(self.blue),
+            file_size: adjust(self.green),
+            dir_size: adjust(self.comment),
+            modified: adjust(self.orange),
+            permissions: adjust(self.yellow),
+            row_number: adjust(self.foreground),
+            extra: ExtraColors::default(),
+        }
+    }
+}
+
+fn luminance(color: Rgb) -> f64 {
+    (0.299 * color.0 as f64 + 0.587 * color.1 as f64 + 0.114 * color.2 as f64) / 255.0
+}
+
+fn brighten(color: Rgb) -> Rgb {
+    let average = (color.0 as f64 + color.1 as f64 + color.2 as f64) / 3.0;
+    if average >= 150.0 {
+        return color;
+    }
+    if average == 0.0 {
+        return (150, 150, 150);
+    }
+    let factor = 150.0 / average;
+    let scale = |c: u8| (c as f64 * factor).min(255.0) as u8;
+    (scale(color.0), scale(color.1), scale(color.2))
+}
+
+/// Accepts `#rrggbb`, `rrggbb` and Alacritty's `0xrrggbb`.
+fn parse_color(value: &str) -> Option<Rgb> {
+    let value = value.trim().trim_matches(['\'', '"']);
+    let hex = value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+        .unwrap_or(value);
+    parse_hex(hex)
+}
+
+/// Turns a scheme name like "Tokyo Night Storm" into `tokyo-night-storm`, the
+/// form used for theme names on the command line.
+pub fn slugify(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_matches('-').to_string()
+}
+
+/// Reads a color scheme and maps its palette onto the theme slots. The theme
+/// is named after `name`, the scheme's own name or the file, in that order.
+pub fn import_theme(
+    path: &Path,
+    format: Option<SchemeFormat>,
+    name: Option<&str>,
+) -> Result<Theme, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let format = format
+        .or_else(|| SchemeFormat::detect(path, &content))
+        .ok_or_else(|| {
+            format!(
+                "{}: cannot tell the scheme format from the extension, pass --from",
+                path.display()
+            )
+        })?;
+
+    let (roles, scheme_name) = match format {
+        SchemeFormat::Base16 => parse_base16(&content)?,
+        SchemeFormat::Alacritty => (parse_alacritty(path, &content)?, None),
+        SchemeFormat::Kitty => (parse_kitty(&content)?, None),
+        SchemeFormat::Iterm2 => (parse_iterm2(&content)?, None),
+    };
+
+    let file_stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let display_name = name
+        .map(str::to_string)
+        .or(scheme_name)
+        .unwrap_or(file_stem);
+    let slug = slugify(&display_name);
+    if slug.is_empty() {
+        return Err(format!("\"{display_name}\" is not a usable theme name, pass --name"));
+    }
+
+    let description = format!("{} - Imported from {}", display_name, format.label());
+    Ok(roles.into_theme(slug, description))
+}
+
+fn parse_base16(content: &str) -> Result<(Roles, Option<String>), String> {
+    let document: Value = serde_yaml::from_str(content).map_err(|e| e.to_string())?;
+    // The tinted-theming format nests the colors under `palette`
+    let palette = document.get("palette").unwrap_or(&document);
+
+    let color = |key: &str| -> Result<Rgb, String> {
+        let value = palette
+            .get(key)
+            .or_else(|| palette.get(key.to_lowercase()))
+            .and_then(Value::as_str)
+            .ok_or_else(|| format!("scheme has no `{key}`"))?;
+        parse_color(value).ok_or_else(|| format!("invalid color \"{value}\" for `{key}`"))
+    };
+
+    let roles = Roles {
+        background: Some(color("base00")?),
+        foreground: color("base05")?,
+        comment: color("base03")?,
+        red: color("base08")?,
+        orange: color("base09")?,
+        yellow: color("base0A")?,
+        green: color("base0B")?,
+        cyan: color("base0C")?,
+        blue: color("base0D")?,
+        magenta: color("base0E")?,
+    };
+    let name = ["name", "scheme"]
+        .iter()
+        .find_map(|key| document.get(key).and_then(Value::as_str))
+        .map(str::to_string);
+    Ok((roles, name))
+}
+
+fn parse_alacritty(path: &Path, content: &str) -> Result<Roles, String> {
+    let is_toml = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("toml"));
+    let document: Value = if is_toml {
+        toml::from_str(content).map_err(|e| e.to_string())?
+    } else {
+        serde_yaml::from_str(content).map_err(|e| e.to_string())?
+    };
+    let colors = document
+        .get("colors")
+        .ok_or("config has no `colors` section")?;
+
+    let lookup = |section: &str, key: &str| {
+        colors
+            .get(section)
+            .and_then(|s| s.get(key))
+            .and_then(Value::as_str)
+            .and_then(parse_color)
+    };
+
+    const NAMES: [&str; 8] = [
+        "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+    ];
+    let mut ansi = [None; 16];
+    for (i, name) in NAMES.iter().enumerate() {
+        ansi[i] = lookup("normal", name);
+        ansi[i + 8] = lookup("bright", name);
+    }
+
+    Roles::from_ansi(
+        lookup("primary", "background"),
+        lookup("primary", "foreground"),
+        &ansi,
+    )
+}
+
+fn parse_kitty(content: &str) -> Result<Roles, String> {
+    let mut background = None;
+    let mut foreground = None;
+    let mut ansi = [None; 16];
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('#') {
+            continue;
+        }
+        let mut parts = line.split_whitespace();
+        let (Some(key), Some(value)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        let Some(color) = parse_color(value) else {
+            continue;
+        };
+        match key {
+            "background" => background = Some(color),
+            "foreground" => foreground = Some(color),
+            _ => {
+                if let Some(index) = key.strip_prefix("color").and_then(|n| n.parse::<usize>().ok())
+                    && index < 16
+                {
+                    ansi[index] = Some(color);
+                }
+            }
+        }
+    }
+
+    Roles::from_ansi(background, foreground, &ansi)
+}
+
+/// Content of the next `<tag>...</tag>` in `input` and the text after it.
+fn next_element<'a>(input: &'a str, tag: &str) -> Option<(&'a str, &'a str)> {
+    let open = format!("<{tag}>");
+    let close = format!("</{tag}>");
+    let start = input.find(&open)? + open.len();
+    let end = start + input[start..].find(&close)?;
+    Some((&input[start..end], &input[end + close.len()..]))
+}
+
+fn parse_iterm2(content: &str) -> Result<Roles, String> {
+    let mut background = None;
+    let mut foreground = None;
+    let mut ansi = [None; 16];
+
+    // The top-level dict maps "<Name> Color" keys to dicts of float components
+    let start = content.find("<dict>").ok_or("not an iTerm2 color file")?;
+    let mut rest = &content[start + "<dict>".len()..];
+
+    while let Some((key, after_key)) = next_element(rest, "key") {
+        let Some((dict, after_dict)) = next_element(after_key, "dict") else {
+            break;
+        };
+        rest = after_dict;
+
+        let mut components = [None; 3];
+        for entry in dict.split("<key>").skip(1) {
+            let Some((component, value)) = entry.split_once("</key>") else {
+                continue;
+            };
+            let index = match component {
+                "Red Component" => 0,
+                "Green Component" => 1,
+                "Blue Component" => 2,
+                _ => continue,
+            };
+            components[index] = next_element(value, "real").and_then(|(v, _)| v.trim().parse::<f64>().ok());
+        }
+        let [Some(r), Some(g), Some(b)] = components else {
+            continue;
+        };
+        let channel = |v: f64| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+        let color = (channel(r), channel(g), channel(b));
+
+        match key {
+            "Background Color" => background = Some(color),
+            "Foreground Color" => foreground = Some(color),
+            _ => {
+                if let Some(index) = key
+                    .strip_prefix("Ansi ")
+                    .and_then(|k| k.strip_suffix(" Color"))
+                    .and_then(|n| n.parse::<usize>().ok())
+                    && index < 16
+                {
+                    ansi[index] = Some(color);
+                }
+            }
+        }
+    }
+
+    Roles::from_ansi(background, foreground, &ansi)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SchemeFormat, import_theme, slugify};
+    use assert_fs::prelude::*;
+
+    #[test]
+    fn test_base16_scheme_maps_named_colors() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let file = dir.child("scheme.yaml");
+        file.write_str(
+            "scheme: \"Test Light\"\nbase00: \"ffffff\"\nbase01: \"eeeeee\"\nbase02: \"dddddd\"\n\
+             base03: \"999999\"\nbase04: \"777777\"\nbase05: \"333333\"\nbase06: \"222222\"\n\
+             base07: \"111111\"\nbase08: \"cc0000\"\nbase09: \"cc6600\"\nbase0A: \"ccaa00\"\n\
+             base0B: \"00aa00\"\nbase0C: \"00aaaa\"\nbase0D: \"0000cc\"\nbase0E: \"aa00aa\"\n\
+             base0F: \"884400\"\n",
+        )
+        .unwrap();
+
+        let theme = import_theme(file.path(), None, None).unwrap();
+        assert_eq!(theme.name, "test-light");
+        // Light background, so the colors are taken as they are
+        assert_eq!(theme.dir_name, (0, 0, 204));
+        assert_eq!(theme.header, (204, 0, 0));
+        assert_eq!(theme.row_number, (51, 51, 51));
+    }
+
+    #[test]
+    fn test_kitty_config_uses_ansi_palette() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let file = dir.child("tokyo night.conf");
+        let mut config = String::from("# comment\nbackground #1a1b26\nforeground #c0caf5\n");
+        for i in 0..16 {
+            config.push_str(&format!("color{i} #{:02x}a0f0\n", i * 10 + 100));
+        }
+        file.write_str(&config).unwrap();
+
+        let theme = import_theme(file.path(), None, None).unwrap();
+        assert_eq!(theme.name, "tokyo-night");
+        assert_eq!(theme.dir_name, (140, 160, 240));
+        assert_eq!(theme.row_number, (192, 202, 245));
+    }
+
+    #[test]
+    fn test_iterm2_plist_components() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let file = dir.child("scheme.itermcolors");
+        let mut plist = String::from("<?xml version=\"1.0\"?>\n<plist version=\"1.0\">\n<dict>\n");
+        for (key, value) in (0..16)
+            .map(|i| (format!("Ansi {i} Color"), "0.8"))
+            .chain([("Background Color".to_string(), "0.0")])
+        {
+            plist.push_str(&format!(
+                "\t<key>{key}</key>\n\t<dict>\n\t\t<key>Alpha Component</key>\n\t\t<real>1</real>\n\
+                 \t\t<key>Blue Component</key>\n\t\t<real>{value}</real>\n\
+                 \t\t<key>Color Space</key>\n\t\t<string>sRGB</string>\n\
+                 \t\t<key>Green Component</key>\n\t\t<real>{value}</real>\n\
+                 \t\t<key>Red Component</key>\n\t\t<real>{value}</real>\n\t</dict>\n"
+            ));
+        }
+        plist.push_str("</dict>\n</plist>\n");
+        file.write_str(&plist).unwrap();
+
+        let theme = import_theme(file.path(), Some(SchemeFormat::Iterm2), Some("Grey")).unwrap();
+        assert_eq!(theme.name, "grey");
+        assert_eq!(theme.border, (204, 204, 204));
+    }
+
+    #[test]
+    fn test_alacritty_toml_brightens_dark_colors() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let file = dir.child("dim.toml");
+        file.write_str(
+            "[colors.primary]\nbackground = \"0x000000\"\nforeground = \"#ffffff\"\n\
+             [colors.normal]\nblack = \"#000000\"\nred = \"#640000\"\ngreen = \"#006400\"\n\
+             yellow = \"#646400\"\nblue = \"#000064\"\nmagenta = \"#640064\"\n\
+             cyan = \"#006464\"\nwhite = \"#c8c8c8\"\n",
+        )
+        .unwrap();
+
+        let theme = import_theme(file.path(), None, None).unwrap();
+        assert_eq!(theme.header, (255, 0, 0));
+        assert_eq!(theme.file_name, (0, 225, 225));
+        assert_eq!(theme.dir_size, (150, 150, 150));
+    }
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Tokyo Night: Storm"), "tokyo-night-storm");
+        assert_eq!(slugify("  --  "), "");
+    }
+}
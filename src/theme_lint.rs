@@ -0,0 +1,341 @@
+use crate::themes::Theme;
+
+type Rgb = (u8, u8, u8);
+
+/// Default background for `lsa theme lint`, a typical dark terminal.
+pub const DEFAULT_BACKGROUND: &str = "#1e1e1e";
+
+// WCAG 2.1: 4.5:1 for normal text, 3:1 for graphical objects such as borders
+const TEXT_CONTRAST: f64 = 4.5;
+const BORDER_CONTRAST: f64 = 3.0;
+// Text below this is hard to read for everyone, not just below the AA level
+const UNREADABLE_CONTRAST: f64 = 3.0;
+const UNREADABLE_BORDER_CONTRAST: f64 = 1.5;
+// CIE76 distance below which two colors are easily mistaken for each other
+const MIN_DISTANCE: f64 = 10.0;
+
+/// Slots that carry different meaning in the same view and therefore have to
+/// be told apart, with what the difference means to the reader.
+const DISTINCT_PAIRS: [(&str, &str, &str); 5] = [
+    ("file_name", "dir_name", "files vs directories"),
+    ("file_size", "dir_size", "file vs directory sizes"),
+    ("git_staged", "git_modified", "staged vs modified git status"),
+    ("git_modified", "git_conflicted", "modified vs conflicted git status"),
+    ("git_untracked", "git_staged", "untracked vs staged git status"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Ok,
+    Warning,
+    Error,
+}
+
+impl Level {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Level::Ok => "ok",
+            Level::Warning => "low contrast",
+            Level::Error => "unreadable",
+        }
+    }
+}
+
+pub struct SlotCheck {
+    pub slot: &'static str,
+    pub color: Rgb,
+    pub contrast: f64,
+    pub minimum: f64,
+    pub level: Level,
+}
+
+/// Two slots that look the same, either to everyone or under one type of
+/// color vision deficiency.
+pub struct PairIssue {
+    pub first: &'static str,
+    pub second: &'static str,
+    pub meaning: &'static str,
+    pub vision: &'static str,
+    pub distance: f64,
+}
+
+pub struct LintReport {
+    pub background: Rgb,
+    pub slots: Vec<SlotCheck>,
+    pub pairs: Vec<PairIssue>,
+}
+
+impl LintReport {
+    pub fn has_problems(&self) -> bool {
+        self.slots.iter().any(|s| s.level != Level::Ok) || !self.pairs.is_empty()
+    }
+}
+
+pub fn lint_theme(theme: &Theme, background: Rgb) -> LintReport {
+    let slots = checked_slots(theme)
+        .map(|(slot, color)| {
+            let (minimum, unreadable) = thresholds(slot);
+            let contrast = contrast_ratio(color, background);
+            let level = if contrast < unreadable {
+                Level::Error
+            } else if contrast < minimum {
+                Level::Warning
+            } else {
+                Level::Ok
+            };
+            SlotCheck {
+                slot,
+                color,
+                contrast,
+                minimum,
+                level,
+            }
+        })
+        .collect();
+
+    let pairs = DISTINCT_PAIRS
+        .iter()
+        .filter_map(|&(first, second, meaning)| {
+            let (vision, distance) = closest_vision(slot(theme, first), slot(theme, second));
+            (distance < MIN_DISTANCE).then_some(PairIssue {
+                first,
+                second,
+                meaning,
+                vision,
+                distance,
+            })
+        })
+        .collect();
+
+    LintReport {
+        background,
+        slots,
+        pairs,
+    }
+}
+
+/// Returns a copy of `theme` where every slot meets its contrast minimum and
+/// the distinct pairs are pushed apart in lightness, which survives all
+/// kinds of color blindness. Slots that already pass are left untouched.
+pub fn fix_theme(theme: &Theme, background: Rgb) -> Theme {
+    let mut fixed = theme.clone();
+    fixed.name = format!("{}-accessible", theme.name);
+    fixed.description = format!("{} (adjusted for contrast)", theme.description);
+
+    // Lighten on dark backgrounds, darken on light ones
+    let target = if relative_luminance(background) < 0.18 {
+        (255, 255, 255)
+    } else {
+        (0, 0, 0)
+    };
+
+    for (name, color) in checked_slots(theme) {
+        let (minimum, _) = thresholds(name);
+        let adjusted = (0..=50)
+            .map(|step| mix(color, target, step as f64 / 50.0))
+            .find(|&c| contrast_ratio(c, background) >= minimum)
+            .unwrap_or(target);
+        if let Some(slot) = fixed.slot_mut(name) {
+            *slot = adjusted;
+        }
+    }
+
+    for (first, second, _) in DISTINCT_PAIRS {
+        let anchor = slot(&fixed, first);
+        let color = slot(&fixed, second);
+        if closest_vision(anchor, color).1 >= MIN_DISTANCE {
+            continue;
+        }
+
+        // Try both directions and keep the smallest change that works
+        let (minimum, _) = thresholds(second);
+        let candidate = (1..=50)
+            .flat_map(|step| {
+                let amount = step as f64 / 50.0;
+                [mix(color, (255, 255, 255), amount), mix(color, (0, 0, 0), amount)]
+            })
+            .find(|&c| {
+                contrast_ratio(c, background) >= minimum
+                    && closest_vision(anchor, c).1 >= MIN_DISTANCE
+            });
+        if let Some(candidate) = candidate {
+            // Keep `*_type` slots in step with the name slot they mirror
+            let twin = second.replace("_name", "_type");
+            for name in [second, twin.as_str()] {
+                if slot(&fixed, name) == color
+                    && let Some(slot) = fixed.slot_mut(name)
+                {
+                    *slot = candidate;
+                }
+            }
+        }
+    }
+
+    fixed
+}
+
+fn thresholds(slot: &str) -> (f64, f64) {
+    if slot == "border" {
+        (BORDER_CONTRAST, UNREADABLE_BORDER_CONTRAST)
+    } else {
+        (TEXT_CONTRAST, UNREADABLE_CONTRAST)
+    }
+}
+
+/// The standard slots and the git slots, which are checked with their
+/// fallbacks since those are what the theme shows.
+fn checked_slots(theme: &Theme) -> impl Iterator<Item = (&'static str, Rgb)> {
+    theme.slots().into_iter().chain(theme.git_slots())
+}
+
+fn slot(theme: &Theme, name: &str) -> Rgb {
+    checked_slots(theme)
+        .find(|(slot, _)| *slot == name)
+        .map(|(_, color)| color)
+        .unwrap_or_default()
+}
+
+fn mix(color: Rgb, target: Rgb, amount: f64) -> Rgb {
+    let channel = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * amount).round() as u8;
+    (
+        channel(color.0, target.0),
+        channel(color.1, target.1),
+        channel(color.2, target.2),
+    )
+}
+
+fn linearize(channel: u8) -> f64 {
+    let c = channel as f64 / 255.0;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_rgb(color: Rgb) -> [f64; 3] {
+    [linearize(color.0), linearize(color.1), linearize(color.2)]
+}
+
+/// WCAG relative luminance.
+pub fn relative_luminance(color: Rgb) -> f64 {
+    let [r, g, b] = linear_rgb(color);
+    0.2126 * r + 0.7152 * g + 0.0722 * b
+}
+
+/// WCAG contrast ratio, from 1:1 (identical) to 21:1 (black on white).
+pub fn contrast_ratio(a: Rgb, b: Rgb) -> f64 {
+    let (la, lb) = (relative_luminance(a), relative_luminance(b));
+    (la.max(lb) + 0.05) / (la.min(lb) + 0.05)
+}
+
+// Machado, Oliveira & Fernandes (2009) simulation matrices at full severity,
+// applied to linear RGB
+const DEFICIENCIES: [(&str, [[f64; 3]; 3]); 3] = [
+    (
+        "protanopia",
+        [
+            [0.152286, 1.052583, -0.204868],
+            [0.114503, 0.786281, 0.099216],
+            [-0.003882, -0.048116, 1.051998],
+        ],
+    ),
+    (
+        "deuteranopia",
+        [
+            [0.367322, 0.860646, -0.227968],
+            [0.280085, 0.672501, 0.047413],
+            [-0.011820, 0.042940, 0.968881],
+        ],
+    ),
+    (
+        "tritanopia",
+        [
+            [1.255528, -0.076749, -0.178779],
+            [-0.078411, 0.930809, 0.147602],
+            [0.004733, 0.691367, 0.303900],
+        ],
+    ),
+];
+
+fn simulate(linear: [f64; 3], matrix: &[[f64; 3]; 3]) -> [f64; 3] {
+    let row = |r: &[f64; 3]| {
+        (r[0] * linear[0] + r[1] * linear[1] + r[2] * linear[2]).clamp(0.0, 1.0)
+    };
+    [row(&matrix[0]), row(&matrix[1]), row(&matrix[2])]
+}
+
+fn lab(linear: [f64; 3]) -> [f64; 3] {
+    let [r, g, b] = linear;
+    // sRGB to XYZ, normalised to the D65 white point
+    let x = (0.4124 * r + 0.3576 * g + 0.1805 * b) / 0.95047;
+    let y = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+    let z = (0.0193 * r + 0.1192 * g + 0.9505 * b) / 1.08883;
+
+    let f = |t: f64| {
+        if t > 0.008856 {
+            t.cbrt()
+        } else {
+            7.787 * t + 16.0 / 116.0
+        }
+    };
+    let (fx, fy, fz) = (f(x), f(y), f(z));
+    [116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz)]
+}
+
+fn distance(a: [f64; 3], b: [f64; 3]) -> f64 {
+    let (la, lb) = (lab(a), lab(b));
+    ((la[0] - lb[0]).powi(2) + (la[1] - lb[1]).powi(2) + (la[2] - lb[2]).powi(2)).sqrt()
+}
+
+/// The kind of vision under which `a` and `b` look most alike, and their
+/// distance there.
+fn closest_vision(a: Rgb, b: Rgb) -> (&'static str, f64) {
+    let (a, b) = (linear_rgb(a), linear_rgb(b));
+    let normal = ("normal vision", distance(a, b));
+    DEFICIENCIES
+        .iter()
+        .map(|(name, matrix)| (*name, distance(simulate(a, matrix), simulate(b, matrix))))
+        .fold(normal, |closest, vision| {
+            if vision.1 < closest.1 { vision } else { closest }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Level, closest_vision, contrast_ratio, fix_theme, lint_theme};
+    use crate::themes::builtin_themes;
+
+    #[test]
+    fn test_contrast_ratio_extremes() {
+        assert!((contrast_ratio((0, 0, 0), (255, 255, 255)) - 21.0).abs() < 0.01);
+        assert!((contrast_ratio((10, 20, 30), (10, 20, 30)) - 1.0).abs() < 0.001);
+        // #777777 on white is the classic just-below-AA gray
+        let ratio = contrast_ratio((119, 119, 119), (255, 255, 255));
+        assert!(ratio > 4.4 && ratio < 4.5, "{ratio}");
+    }
+
+    #[test]
+    fn test_red_green_confusable_for_deuteranopia() {
+        let (vision, distance) = closest_vision((200, 90, 60), (120, 130, 40));
+        assert!(vision == "deuteranopia" || vision == "protanopia", "{vision}");
+        assert!(distance < 10.0, "{distance}");
+    }
+
+    #[test]
+    fn test_fixed_theme_passes_lint() {
+        let mut theme = builtin_themes().remove(0);
+        theme.file_name = (60, 60, 60);
+        theme.dir_name = (58, 62, 60);
+        let background = (30, 30, 30);
+
+        let report = lint_theme(&theme, background);
+        assert!(report.slots.iter().any(|s| s.level == Level::Error));
+        assert!(report.pairs.iter().any(|p| p.second == "dir_name"));
+
+        let fixed = fix_theme(&theme, background);
+        let report = lint_theme(&fixed, background);
+        assert!(report.slots.iter().all(|s| s.level == Level::Ok));
+        assert!(!report.pairs.iter().any(|p| p.second == "dir_name"));
+    }
+}
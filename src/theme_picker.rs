@@ -0,0 +1,213 @@
+use std::io::{self, IsTerminal, Write};
+
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::style::{Attribute, Print, SetAttribute};
+use crossterm::terminal::{
+    self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use crossterm::{execute, queue};
+
+use crate::display::render_theme_preview;
+use crate::grid::clip_to_width;
+use crate::themes::Theme;
+
+const LIST_HEIGHT: usize = 8;
+const PROMPT: &str = "Select a theme to set as default:";
+const HINT: &str = "↑↓ to move, enter to select, type to filter, esc to cancel";
+
+/// Restores the terminal even when drawing fails half way.
+struct RawScreen;
+
+impl RawScreen {
+    fn enter() -> io::Result<RawScreen> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), EnterAlternateScreen, Hide)?;
+        Ok(RawScreen)
+    }
+}
+
+impl Drop for RawScreen {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+struct Picker<'a> {
+    themes: &'a [Theme],
+    filter: String,
+    // Indices into `themes` matching the filter
+    matches: Vec<usize>,
+    selected: usize,
+    scroll: usize,
+}
+
+impl<'a> Picker<'a> {
+    fn new(themes: &'a [Theme], current: &str) -> Self {
+        let mut picker = Picker {
+            themes,
+            filter: String::new(),
+            matches: Vec::new(),
+            selected: 0,
+            scroll: 0,
+        };
+        picker.update_matches();
+        picker.selected = themes.iter().position(|t| t.name == current).unwrap_or(0);
+        picker
+    }
+
+    fn update_matches(&mut self) {
+        let filter = self.filter.to_lowercase();
+        self.matches = (0..self.themes.len())
+            .filter(|&i| {
+                let theme = &self.themes[i];
+                theme.name.to_lowercase().contains(&filter)
+                    || theme.description.to_lowercase().contains(&filter)
+            })
+            .collect();
+        self.selected = 0;
+        self.scroll = 0;
+    }
+
+    fn current(&self) -> Option<&'a Theme> {
+        self.matches.get(self.selected).map(|&i| &self.themes[i])
+    }
+
+    fn move_by(&mut self, delta: isize) {
+        if self.matches.is_empty() {
+            return;
+        }
+        let last = self.matches.len() - 1;
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
+
+    fn draw(&mut self, out: &mut impl Write) -> io::Result<()> {
+        let (width, height) = terminal::size()?;
+        let (width, height) = (width as usize, height as usize);
+
+        // Keep the selection inside the visible window of the list
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        } else if self.selected >= self.scroll + LIST_HEIGHT {
+            self.scroll = self.selected + 1 - LIST_HEIGHT;
+        }
+
+        let mut lines: Vec<(String, bool)> =
+            vec![(format!("? {} {}", PROMPT, self.filter), false)];
+        for (offset, &index) in self
+            .matches
+            .iter()
+            .enumerate()
+            .skip(self.scroll)
+            .take(LIST_HEIGHT)
+        {
+            let theme = &self.themes[index];
+            let marker = if offset == self.selected { ">" } else { " " };
+            lines.push((
+                format!("{} {} - {}", marker, theme.name, theme.description),
+                offset == self.selected,
+            ));
+        }
+        if self.matches.is_empty() {
+            lines.push(("  No matching themes".to_string(), false));
+        }
+        lines.push((format!("[{HINT}]"), false));
+
+        queue!(out, MoveTo(0, 0), Clear(ClearType::All))?;
+        for (row, (line, highlighted)) in lines.iter().enumerate() {
+            let line = clip_to_width(line, width);
+            queue!(out, MoveTo(0, row as u16))?;
+            if *highlighted {
+                queue!(
+                    out,
+                    SetAttribute(Attribute::Reverse),
+                    Print(line),
+                    SetAttribute(Attribute::Reset)
+                )?;
+            } else {
+                queue!(out, Print(line))?;
+            }
+        }
+
+        if let Some(theme) = self.current() {
+            let top = lines.len() + 1;
+            let preview = render_theme_preview(theme);
+            for (row, line) in preview.lines().enumerate().take(height.saturating_sub(top)) {
+                // Lines wider than the terminal would wrap over the next rows
+                queue!(out, MoveTo(0, (top + row) as u16), Print(clip_to_width(line, width)))?;
+            }
+        }
+        out.flush()
+    }
+}
+
+/// Interactive theme list with a live preview of the highlighted theme.
+/// Returns `None` when the selection is cancelled.
+pub fn pick_theme(themes: &[Theme], current: &str) -> io::Result<Option<String>> {
+    if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+        return Err(io::Error::other(
+            "the theme picker needs a terminal, pass a theme name instead",
+        ));
+    }
+
+    let _screen = RawScreen::enter()?;
+    let mut picker = Picker::new(themes, current);
+    let mut stdout = io::stdout();
+
+    loop {
+        picker.draw(&mut stdout)?;
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        match key.code {
+            KeyCode::Esc => return Ok(None),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return Ok(None);
+            }
+            KeyCode::Enter => return Ok(picker.current().map(|t| t.name.clone())),
+            KeyCode::Up => picker.move_by(-1),
+            KeyCode::Down => picker.move_by(1),
+            KeyCode::PageUp => picker.move_by(-(LIST_HEIGHT as isize)),
+            KeyCode::PageDown => picker.move_by(LIST_HEIGHT as isize),
+            KeyCode::Home => picker.selected = 0,
+            KeyCode::End => picker.move_by(isize::MAX),
+            KeyCode::Backspace => {
+                picker.filter.pop();
+                picker.update_matches();
+            }
+            KeyCode::Char(c) => {
+                picker.filter.push(c);
+                picker.update_matches();
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Picker;
+    use crate::themes::builtin_themes;
+
+    #[test]
+    fn test_filter_matches_name_and_description() {
+        let themes = builtin_themes();
+        let mut picker = Picker::new(&themes, "nord");
+        assert_eq!(picker.current().unwrap().name, "nord");
+
+        picker.filter = "DRACULA".to_string();
+        picker.update_matches();
+        assert_eq!(picker.current().unwrap().name, "dracula");
+
+        picker.filter = "no theme has this".to_string();
+        picker.update_matches();
+        assert!(picker.current().is_none());
+        picker.move_by(1);
+    }
+}
@@ -1,6 +1,8 @@
+use std::sync::OnceLock;
 
+use crate::user_themes::load_user_themes;
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub struct Theme {
     pub name: String,
     pub description: String,
@@ -15,9 +17,199 @@ pub struct Theme {
     pub modified: (u8, u8, u8),
     pub permissions: (u8, u8, u8),
     pub row_number: (u8, u8, u8),
+    // Slots added later, see `ExtraColors`
+    pub extra: ExtraColors,
 }
 
-pub fn get_themes() -> Vec<Theme> {
+type OptionalSlot = (&'static str, Option<(u8, u8, u8)>);
+
+/// Color slots added after the first themes were written. Themes that leave
+/// one unset get a fallback instead, so built-in themes start from
+/// `ExtraColors::default()` and a new slot needs no change to them.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExtraColors {
+    pub symlink: Option<(u8, u8, u8)>,
+    pub broken_link: Option<(u8, u8, u8)>,
+    // Optional table columns
+    pub owner: Option<(u8, u8, u8)>,
+    pub group: Option<(u8, u8, u8)>,
+    pub inode: Option<(u8, u8, u8)>,
+    pub links: Option<(u8, u8, u8)>,
+    pub atime: Option<(u8, u8, u8)>,
+    pub ctime: Option<(u8, u8, u8)>,
+    pub btime: Option<(u8, u8, u8)>,
+    pub octal: Option<(u8, u8, u8)>,
+    // Git status markers
+    pub git_modified: Option<(u8, u8, u8)>,
+    pub git_staged: Option<(u8, u8, u8)>,
+    pub git_untracked: Option<(u8, u8, u8)>,
+    pub git_ignored: Option<(u8, u8, u8)>,
+    pub git_conflicted: Option<(u8, u8, u8)>,
+}
+
+impl ExtraColors {
+    /// The slots by the name used in theme files.
+    pub fn slots(&self) -> [OptionalSlot; 15] {
+        [
+            ("symlink", self.symlink),
+            ("broken_link", self.broken_link),
+            ("owner", self.owner),
+            ("group", self.group),
+            ("inode", self.inode),
+            ("links", self.links),
+            ("atime", self.atime),
+            ("ctime", self.ctime),
+            ("btime", self.btime),
+            ("octal", self.octal),
+            ("git_modified", self.git_modified),
+            ("git_staged", self.git_staged),
+            ("git_untracked", self.git_untracked),
+            ("git_ignored", self.git_ignored),
+            ("git_conflicted", self.git_conflicted),
+        ]
+    }
+}
+
+impl Theme {
+    /// Colors of the git status markers unless the theme sets them: purple,
+    /// green, cyan and gray, which stay apart for color blind readers too.
+    /// Conflicts share the broken link red.
+    pub fn git_modified_color(&self) -> (u8, u8, u8) {
+        self.extra.git_modified.unwrap_or((189, 147, 249))
+    }
+
+    pub fn git_staged_color(&self) -> (u8, u8, u8) {
+        self.extra.git_staged.unwrap_or((80, 250, 123))
+    }
+
+    pub fn git_untracked_color(&self) -> (u8, u8, u8) {
+        self.extra.git_untracked.unwrap_or((139, 233, 253))
+    }
+
+    pub fn git_ignored_color(&self) -> (u8, u8, u8) {
+        self.extra.git_ignored.unwrap_or((150, 150, 150))
+    }
+
+    pub fn git_conflicted_color(&self) -> (u8, u8, u8) {
+        self.extra.git_conflicted.unwrap_or_else(|| self.broken_link_color())
+    }
+
+    /// The git slots with their fallbacks applied, for `lsa theme lint`.
+    pub fn git_slots(&self) -> [(&'static str, (u8, u8, u8)); 5] {
+        [
+            ("git_modified", self.git_modified_color()),
+            ("git_staged", self.git_staged_color()),
+            ("git_untracked", self.git_untracked_color()),
+            ("git_ignored", self.git_ignored_color()),
+            ("git_conflicted", self.git_conflicted_color()),
+        ]
+    }
+}
+
+/// Names of the color slots, in the order returned by `Theme::slots`.
+pub const SLOT_NAMES: [&str; 11] = [
+    "border",
+    "header",
+    "file_name",
+    "file_type",
+    "dir_name",
+    "dir_type",
+    "file_size",
+    "dir_size",
+    "modified",
+    "permissions",
+    "row_number",
+];
+
+impl Theme {
+    /// Every color slot with its field name, in declaration order.
+    pub fn slots(&self) -> [(&'static str, (u8, u8, u8)); 11] {
+        [
+            ("border", self.border),
+            ("header", self.header),
+            ("file_name", self.file_name),
+            ("file_type", self.file_type),
+            ("dir_name", self.dir_name),
+            ("dir_type", self.dir_type),
+            ("file_size", self.file_size),
+            ("dir_size", self.dir_size),
+            ("modified", self.modified),
+            ("permissions", self.permissions),
+            ("row_number", self.row_number),
+        ]
+    }
+
+    /// Color of symlink names, the header color unless the theme sets one.
+    pub fn symlink_color(&self) -> (u8, u8, u8) {
+        self.extra.symlink.unwrap_or(self.header)
+    }
+
+    /// Color of symlinks whose target is missing, red unless the theme sets one.
+    pub fn broken_link_color(&self) -> (u8, u8, u8) {
+        self.extra.broken_link.unwrap_or((255, 85, 85))
+    }
+
+    /// Colors of the optional columns, falling back to the closest standard slot.
+    pub fn owner_color(&self) -> (u8, u8, u8) {
+        self.extra.owner.unwrap_or(self.file_type)
+    }
+
+    pub fn group_color(&self) -> (u8, u8, u8) {
+        self.extra.group.unwrap_or(self.dir_type)
+    }
+
+    pub fn inode_color(&self) -> (u8, u8, u8) {
+        self.extra.inode.unwrap_or(self.row_number)
+    }
+
+    pub fn links_color(&self) -> (u8, u8, u8) {
+        self.extra.links.unwrap_or(self.row_number)
+    }
+
+    pub fn atime_color(&self) -> (u8, u8, u8) {
+        self.extra.atime.unwrap_or(self.modified)
+    }
+
+    pub fn ctime_color(&self) -> (u8, u8, u8) {
+        self.extra.ctime.unwrap_or(self.modified)
+    }
+
+    pub fn btime_color(&self) -> (u8, u8, u8) {
+        self.extra.btime.unwrap_or(self.modified)
+    }
+
+    pub fn octal_color(&self) -> (u8, u8, u8) {
+        self.extra.octal.unwrap_or(self.permissions)
+    }
+
+    pub fn slot_mut(&mut self, slot: &str) -> Option<&mut (u8, u8, u8)> {
+        match slot {
+            "border" => Some(&mut self.border),
+            "header" => Some(&mut self.header),
+            "file_name" => Some(&mut self.file_name),
+            "file_type" => Some(&mut self.file_type),
+            "dir_name" => Some(&mut self.dir_name),
+            "dir_type" => Some(&mut self.dir_type),
+            "file_size" => Some(&mut self.file_size),
+            "dir_size" => Some(&mut self.dir_size),
+            "modified" => Some(&mut self.modified),
+            "permissions" => Some(&mut self.permissions),
+            "row_number" => Some(&mut self.row_number),
+            // Unset git slots start from their fallback
+            "git_modified" => Some(self.extra.git_modified.insert(self.git_modified_color())),
+            "git_staged" => Some(self.extra.git_staged.insert(self.git_staged_color())),
+            "git_untracked" => Some(self.extra.git_untracked.insert(self.git_untracked_color())),
+            "git_ignored" => Some(self.extra.git_ignored.insert(self.git_ignored_color())),
+            "git_conflicted" => {
+                let color = self.git_conflicted_color();
+                Some(self.extra.git_conflicted.insert(color))
+            }
+            _ => None,
+        }
+    }
+}
+
+pub fn builtin_themes() -> Vec<Theme> {
     vec![
         Theme {
             name: "catppuccin".to_string(),
@@ -33,6 +225,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (249, 226, 175),
             permissions: (148, 226, 213),
             row_number: (205, 214, 244),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "nord".to_string(),
@@ -48,6 +241,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (235, 203, 139),
             permissions: (180, 142, 173),
             row_number: (216, 222, 233),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "dracula".to_string(),
@@ -63,6 +257,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 184, 108),
             permissions: (241, 250, 140),
             row_number: (248, 248, 242),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "monokai".to_string(),
@@ -78,6 +273,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 216, 102),
             permissions: (255, 97, 136),
             row_number: (248, 248, 242),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "gruvbox".to_string(),
@@ -93,6 +289,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (241, 171, 37),
             permissions: (194, 107, 147),
             row_number: (235, 219, 178),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "solarized".to_string(),
@@ -108,6 +305,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 193, 0),
             permissions: (116, 121, 211),
             row_number: (137, 155, 157),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "tokyo-night".to_string(),
@@ -123,6 +321,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 158, 100),
             permissions: (255, 203, 107),
             row_number: (169, 177, 214),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "onedark".to_string(),
@@ -138,6 +337,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (229, 192, 123),
             permissions: (86, 182, 194),
             row_number: (171, 178, 191),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "material".to_string(),
@@ -153,6 +353,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 168, 0),
             permissions: (0, 211, 238),
             row_number: (150, 150, 150),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "oceanic-next".to_string(),
@@ -168,6 +369,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (250, 208, 122),
             permissions: (100, 189, 159),
             row_number: (160, 168, 180),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "ayu-dark".to_string(),
@@ -183,6 +385,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 162, 25),
             permissions: (128, 203, 196),
             row_number: (151, 165, 180),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "synthwave".to_string(),
@@ -198,6 +401,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 176, 0),
             permissions: (255, 0, 255),
             row_number: (255, 255, 255),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "github-dark".to_string(),
@@ -213,6 +417,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 235, 59),
             permissions: (164, 196, 255),
             row_number: (201, 209, 217),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "cobalt2".to_string(),
@@ -228,6 +433,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 198, 109),
             permissions: (255, 157, 77),
             row_number: (193, 193, 193),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "palenight".to_string(),
@@ -243,6 +449,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 158, 100),
             permissions: (137, 221, 255),
             row_number: (171, 178, 191),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "night-owl".to_string(),
@@ -258,6 +465,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 158, 100),
             permissions: (137, 221, 255),
             row_number: (214, 222, 235),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "horizon".to_string(),
@@ -273,6 +481,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (236, 196, 141),
             permissions: (229, 152, 155),
             row_number: (203, 204, 198),
+            extra: ExtraColors::default(),
          },
         Theme {
             name: "Aurora Borealis".to_string(),
@@ -288,6 +497,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 150, 0),
             permissions: (255, 0, 150),
             row_number: (192, 192, 192),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Sunset Glow".to_string(),
@@ -303,6 +513,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 21, 156),
             permissions: (255, 30, 90),
             row_number: (245, 245, 220),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Midnight Blue".to_string(),
@@ -318,6 +529,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (0, 223, 226),
             permissions: (37, 210, 201),
             row_number: (176, 196, 222),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Forest Green".to_string(),
@@ -333,6 +545,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 215, 0),
             permissions: (255, 95, 0),
             row_number: (240, 255, 240),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Desert Sand".to_string(),
@@ -348,6 +561,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 159, 0),
             permissions: (237, 106, 106),
             row_number: (255, 250, 205),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Ocean Wave".to_string(),
@@ -363,6 +577,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (0, 255, 255),
             permissions: (0, 225, 225),
             row_number: (176, 224, 230),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Cherry Blossom".to_string(),
@@ -378,6 +593,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 95, 0),
             permissions: (255, 30, 90),
             row_number: (255, 228, 225),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Autumn Leaves".to_string(),
@@ -393,6 +609,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (237, 106, 106),
             permissions: (255, 57, 88),
             row_number: (255, 248, 220),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Winter Frost".to_string(),
@@ -408,6 +625,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (0, 192, 255),
             permissions: (82, 153, 213),
             row_number: (255, 255, 255),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Spring Meadow".to_string(),
@@ -423,6 +641,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 215, 0),
             permissions: (255, 95, 0),
             row_number: (240, 255, 240),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Crimson Tide".to_string(),
@@ -438,6 +657,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 21, 156),
             permissions: (255, 0, 255),
             row_number: (255, 228, 225),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Azure Sky".to_string(),
@@ -453,6 +673,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (37, 210, 201),
             permissions: (0, 225, 225),
             row_number: (176, 224, 230),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Emerald Isle".to_string(),
@@ -468,6 +689,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 215, 0),
             permissions: (255, 95, 0),
             row_number: (240, 255, 240),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Golden Hour".to_string(),
@@ -483,6 +705,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 95, 0),
             permissions: (237, 106, 106),
             row_number: (255, 250, 205),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Twilight Zone".to_string(),
@@ -498,6 +721,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 0, 255),
             permissions: (255, 21, 156),
             row_number: (211, 211, 211),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Neon Nights".to_string(),
@@ -513,6 +737,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 176, 0),
             permissions: (255, 21, 156),
             row_number: (255, 255, 255),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Pastel Dreams".to_string(),
@@ -528,6 +753,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 182, 193),
             permissions: (255, 160, 122),
             row_number: (255, 250, 250),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Monochrome".to_string(),
@@ -543,6 +769,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (150, 150, 150),
             permissions: (255, 255, 255),
             row_number: (224, 224, 224),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Rainbow Burst".to_string(),
@@ -558,6 +785,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (225, 0, 225),
             permissions: (255, 0, 255),
             row_number: (255, 255, 255),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Mystic Purple".to_string(),
@@ -573,6 +801,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 21, 156),
             permissions: (225, 0, 225),
             row_number: (230, 230, 250),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Fiery Orange".to_string(),
@@ -588,6 +817,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 0, 0),
             permissions: (255, 57, 88),
             row_number: (255, 248, 220),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Cool Breeze".to_string(),
@@ -603,6 +833,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (37, 210, 201),
             permissions: (0, 225, 225),
             row_number: (176, 224, 230),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Warm Hearth".to_string(),
@@ -618,6 +849,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 95, 0),
             permissions: (237, 106, 106),
             row_number: (255, 228, 196),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Electric Lime".to_string(),
@@ -633,6 +865,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 215, 0),
             permissions: (255, 95, 0),
             row_number: (240, 255, 240),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Royal Blue".to_string(),
@@ -648,6 +881,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (0, 192, 255),
             permissions: (0, 223, 226),
             row_number: (176, 196, 222),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Lavender Fields".to_string(),
@@ -663,6 +897,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 21, 156),
             permissions: (225, 0, 225),
             row_number: (248, 248, 255),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Coral Reef".to_string(),
@@ -678,6 +913,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 30, 90),
             permissions: (255, 57, 88),
             row_number: (255, 228, 225),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Arctic White".to_string(),
@@ -693,6 +929,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (173, 216, 230),
             permissions: (135, 206, 235),
             row_number: (255, 255, 255),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Volcanic Red".to_string(),
@@ -708,6 +945,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 21, 156),
             permissions: (255, 0, 255),
             row_number: (255, 228, 225),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Tropical Paradise".to_string(),
@@ -723,6 +961,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 0, 255),
             permissions: (0, 255, 255),
             row_number: (255, 255, 255),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Cyberpunk".to_string(),
@@ -738,6 +977,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 176, 0),
             permissions: (255, 21, 156),
             row_number: (150, 150, 150),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Retro Arcade".to_string(),
@@ -753,6 +993,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 176, 0),
             permissions: (0, 255, 255),
             row_number: (255, 255, 255),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Minimalist Gray".to_string(),
@@ -768,6 +1009,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (150, 150, 150),
             permissions: (255, 255, 255),
             row_number: (245, 245, 245),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Vibrant Violet".to_string(),
@@ -783,6 +1025,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 21, 156),
             permissions: (225, 0, 225),
             row_number: (230, 230, 250),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Sunny Yellow".to_string(),
@@ -798,6 +1041,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 0, 0),
             permissions: (237, 106, 106),
             row_number: (255, 250, 205),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Deep Sea".to_string(),
@@ -813,6 +1057,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (0, 223, 226),
             permissions: (37, 210, 201),
             row_number: (176, 196, 222),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Mountain Peak".to_string(),
@@ -828,6 +1073,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 255, 255),
             permissions: (150, 150, 150),
             row_number: (245, 245, 245),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "River Flow".to_string(),
@@ -843,6 +1089,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (37, 210, 201),
             permissions: (0, 225, 225),
             row_number: (240, 248, 255),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Starry Night".to_string(),
@@ -858,6 +1105,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 255, 0),
             permissions: (255, 215, 0),
             row_number: (176, 196, 222),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Moonlight".to_string(),
@@ -873,6 +1121,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 255, 255),
             permissions: (150, 150, 150),
             row_number: (245, 245, 245),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Sunrise".to_string(),
@@ -888,6 +1137,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 21, 156),
             permissions: (255, 30, 90),
             row_number: (255, 228, 225),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Dusk".to_string(),
@@ -903,6 +1153,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 0, 255),
             permissions: (255, 21, 156),
             row_number: (230, 230, 250),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Dawn".to_string(),
@@ -918,6 +1169,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 21, 156),
             permissions: (255, 105, 180),
             row_number: (255, 250, 250),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Eclipse".to_string(),
@@ -933,6 +1185,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 255, 0),
             permissions: (255, 0, 255),
             row_number: (192, 192, 192),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Comet".to_string(),
@@ -948,6 +1201,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 255, 0),
             permissions: (255, 0, 255),
             row_number: (240, 248, 255),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Galaxy".to_string(),
@@ -963,6 +1217,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 255, 0),
             permissions: (255, 21, 156),
             row_number: (230, 230, 250),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Nebula".to_string(),
@@ -978,6 +1233,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 176, 0),
             permissions: (255, 21, 156),
             row_number: (255, 255, 255),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Supernova".to_string(),
@@ -993,6 +1249,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 0, 255),
             permissions: (225, 0, 225),
             row_number: (255, 255, 255),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Black Hole".to_string(),
@@ -1008,6 +1265,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 0, 0),
             permissions: (255, 255, 0),
             row_number: (150, 150, 150),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Cosmic Dust".to_string(),
@@ -1023,6 +1281,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 255, 0),
             permissions: (255, 0, 255),
             row_number: (245, 245, 245),
+            extra: ExtraColors::default(),
          },
         Theme {
             name: "Ocean Depths".to_string(),
@@ -1038,6 +1297,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (120, 160, 200),
             permissions: (100, 150, 200),
             row_number: (75, 150, 225),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Forest Whisper".to_string(),
@@ -1053,6 +1313,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (140, 180, 140),
             permissions: (132, 185, 132),
             row_number: (122, 204, 122),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Desert Mirage".to_string(),
@@ -1068,6 +1329,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (194, 163, 92),
             permissions: (205, 167, 77),
             row_number: (216, 162, 72),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Mountain Peak".to_string(),
@@ -1083,6 +1345,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (170, 170, 170),
             permissions: (150, 150, 150),
             row_number: (150, 150, 150),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Tropical Storm".to_string(),
@@ -1098,6 +1361,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (167, 73, 209),
             permissions: (160, 64, 225),
             row_number: (150, 50, 250),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Autumn Harvest".to_string(),
@@ -1113,6 +1377,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 133, 60),
             permissions: (255, 121, 34),
             row_number: (255, 118, 23),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Winter Frost".to_string(),
@@ -1128,6 +1393,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (85, 150, 214),
             permissions: (60, 150, 240),
             row_number: (45, 135, 255),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Spring Blossom".to_string(),
@@ -1143,6 +1409,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (210, 110, 170),
             permissions: (206, 85, 158),
             row_number: (216, 83, 150),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Summer Solstice".to_string(),
@@ -1158,6 +1425,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (230, 210, 60),
             permissions: (224, 201, 23),
             row_number: (232, 201, 15),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Cosmic Nebula".to_string(),
@@ -1173,6 +1441,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (200, 80, 200),
             permissions: (192, 64, 192),
             row_number: (200, 50, 200),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Volcanic Lava".to_string(),
@@ -1188,6 +1457,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 85, 85),
             permissions: (255, 56, 56),
             row_number: (255, 32, 32),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Arctic Aurora".to_string(),
@@ -1203,6 +1473,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (90, 180, 180),
             permissions: (64, 192, 192),
             row_number: (50, 200, 200),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Jungle Canopy".to_string(),
@@ -1218,6 +1489,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (157, 202, 90),
             permissions: (150, 225, 75),
             row_number: (128, 255, 64),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Savannah Sunset".to_string(),
@@ -1233,6 +1505,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (250, 137, 62),
             permissions: (255, 136, 39),
             row_number: (255, 140, 28),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Crystal Cave".to_string(),
@@ -1248,6 +1521,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (160, 160, 200),
             permissions: (132, 132, 185),
             row_number: (122, 122, 204),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Thundercloud".to_string(),
@@ -1263,6 +1537,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (150, 150, 150),
             permissions: (150, 150, 150),
             row_number: (150, 150, 150),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Coral Reef".to_string(),
@@ -1278,6 +1553,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (220, 90, 150),
             permissions: (232, 72, 145),
             row_number: (225, 67, 157),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Glacier Ice".to_string(),
@@ -1293,6 +1569,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (100, 160, 200),
             permissions: (75, 150, 225),
             row_number: (54, 144, 252),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Prairie Grass".to_string(),
@@ -1308,6 +1585,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (160, 220, 80),
             permissions: (150, 240, 60),
             row_number: (150, 250, 50),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Midnight Sky".to_string(),
@@ -1323,6 +1601,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (112, 112, 225),
             permissions: (90, 90, 255),
             row_number: (75, 75, 255),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Cherry Blossom".to_string(),
@@ -1338,6 +1617,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (220, 90, 150),
             permissions: (232, 72, 145),
             row_number: (225, 67, 157),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Amber Glow".to_string(),
@@ -1353,6 +1633,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (252, 142, 54),
             permissions: (255, 128, 32),
             row_number: (255, 142, 23),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Emerald Forest".to_string(),
@@ -1368,6 +1649,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (105, 211, 132),
             permissions: (81, 245, 122),
             row_number: (64, 255, 128),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Ruby Flame".to_string(),
@@ -1383,6 +1665,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 85, 85),
             permissions: (255, 56, 56),
             row_number: (255, 32, 32),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Sapphire Wave".to_string(),
@@ -1398,6 +1681,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (90, 135, 225),
             permissions: (64, 128, 255),
             row_number: (50, 100, 255),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Golden Sands".to_string(),
@@ -1413,6 +1697,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (220, 200, 60),
             permissions: (225, 196, 28),
             row_number: (236, 189, 23),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Ivory Moon".to_string(),
@@ -1428,6 +1713,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (180, 180, 180),
             permissions: (150, 150, 150),
             row_number: (150, 150, 150),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Crimson Tide".to_string(),
@@ -1443,6 +1729,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (231, 85, 133),
             permissions: (245, 61, 143),
             row_number: (255, 32, 160),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Azure Horizon".to_string(),
@@ -1458,6 +1745,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (80, 160, 220),
             permissions: (52, 158, 238),
             row_number: (37, 150, 255),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Bronze Age".to_string(),
@@ -1473,6 +1761,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (235, 139, 75),
             permissions: (255, 133, 50),
             row_number: (255, 136, 40),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Platinum Frost".to_string(),
@@ -1488,6 +1777,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (200, 200, 220),
             permissions: (142, 142, 164),
             row_number: (139, 139, 170),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Violet Storm".to_string(),
@@ -1503,6 +1793,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (170, 80, 210),
             permissions: (165, 60, 225),
             row_number: (150, 42, 255),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Copper Sunrise".to_string(),
@@ -1518,6 +1809,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (255, 109, 60),
             permissions: (255, 90, 36),
             row_number: (255, 84, 28),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Jade Garden".to_string(),
@@ -1533,6 +1825,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (100, 225, 125),
             permissions: (75, 255, 112),
             row_number: (56, 255, 112),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Obsidian Night".to_string(),
@@ -1548,6 +1841,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (150, 150, 150),
             permissions: (150, 150, 150),
             row_number: (150, 150, 150),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Pearl Mist".to_string(),
@@ -1563,6 +1857,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (210, 210, 230),
             permissions: (150, 150, 170),
             row_number: (141, 141, 167),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Cinnamon Spice".to_string(),
@@ -1578,6 +1873,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (247, 123, 78),
             permissions: (255, 108, 54),
             row_number: (255, 116, 43),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Mint Breeze".to_string(),
@@ -1593,6 +1889,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (80, 220, 160),
             permissions: (52, 238, 158),
             row_number: (40, 245, 163),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Onyx Shadow".to_string(),
@@ -1608,6 +1905,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (150, 150, 150),
             permissions: (150, 150, 150),
             row_number: (150, 150, 150),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Lavender Field".to_string(),
@@ -1623,6 +1921,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (190, 90, 220),
             permissions: (172, 66, 211),
             row_number: (168, 56, 225),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Turquoise Lagoon".to_string(),
@@ -1638,6 +1937,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (80, 160, 220),
             permissions: (52, 158, 238),
             row_number: (37, 150, 255),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Rose Petal".to_string(),
@@ -1653,6 +1953,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (230, 90, 150),
             permissions: (245, 68, 136),
             row_number: (245, 61, 143),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Steel Forge".to_string(),
@@ -1668,6 +1969,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (160, 160, 160),
             permissions: (150, 150, 150),
             row_number: (150, 150, 150),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Honeycomb".to_string(),
@@ -1683,6 +1985,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (230, 190, 50),
             permissions: (238, 185, 26),
             row_number: (234, 195, 19),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Mossy Stone".to_string(),
@@ -1698,6 +2001,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (145, 194, 109),
             permissions: (136, 215, 97),
             row_number: (128, 225, 96),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Cobalt Dream".to_string(),
@@ -1713,6 +2017,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (85, 128, 235),
             permissions: (60, 120, 255),
             row_number: (45, 90, 255),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Burgundy Wine".to_string(),
@@ -1728,6 +2033,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (242, 80, 126),
             permissions: (255, 56, 131),
             row_number: (255, 43, 145),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Teal Abyss".to_string(),
@@ -1743,6 +2049,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (85, 150, 214),
             permissions: (60, 150, 240),
             row_number: (45, 135, 255),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Slate Cliff".to_string(),
@@ -1758,6 +2065,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (140, 140, 180),
             permissions: (128, 128, 192),
             row_number: (125, 125, 200),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Champagne Bubbles".to_string(),
@@ -1773,6 +2081,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (220, 180, 80),
             permissions: (225, 168, 56),
             row_number: (225, 163, 61),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Pine Forest".to_string(),
@@ -1788,6 +2097,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (126, 196, 126),
             permissions: (112, 225, 112),
             row_number: (112, 225, 112),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Lemon Zest".to_string(),
@@ -1803,6 +2113,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (230, 210, 60),
             permissions: (224, 201, 23),
             row_number: (232, 201, 15),
+            extra: ExtraColors::default(),
         },
         Theme {
             name: "Indigo Dusk".to_string(),
@@ -1818,6 +2129,7 @@ pub fn get_themes() -> Vec<Theme> {
             modified: (160, 85, 203),
             permissions: (155, 69, 224),
             row_number: (150, 50, 250),
+            extra: ExtraColors::default(),
         },
          Theme {
              name: "Ocean Breeze".to_string(),
@@ -1833,6 +2145,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (0, 223, 226),
              permissions: (72, 209, 204),
              row_number: (176, 224, 230),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Forest Green".to_string(),
@@ -1848,6 +2161,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (73, 255, 73),
              permissions: (76, 228, 144),
              row_number: (152, 251, 152),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Mountain Peak".to_string(),
@@ -1863,6 +2177,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (150, 150, 150),
              permissions: (150, 150, 150),
              row_number: (245, 245, 245),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Desert Sand".to_string(),
@@ -1878,6 +2193,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (210, 180, 140),
              permissions: (222, 184, 135),
              row_number: (255, 239, 213),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Arctic Ice".to_string(),
@@ -1893,6 +2209,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (176, 224, 230),
              permissions: (82, 153, 213),
              row_number: (240, 248, 255),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Tropical Sunset".to_string(),
@@ -1908,6 +2225,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (255, 95, 0),
              permissions: (255, 127, 80),
              row_number: (255, 228, 196),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Autumn Leaves".to_string(),
@@ -1923,6 +2241,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (255, 95, 0),
              permissions: (255, 104, 75),
              row_number: (255, 218, 185),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Spring Blossom".to_string(),
@@ -1938,6 +2257,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (255, 182, 193),
              permissions: (255, 105, 180),
              row_number: (255, 250, 250),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Midnight Sky".to_string(),
@@ -1953,6 +2273,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (150, 150, 150),
              permissions: (0, 0, 255),
              row_number: (54, 54, 255),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Sunrise Glow".to_string(),
@@ -1968,6 +2289,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (255, 215, 0),
              permissions: (255, 159, 0),
              row_number: (255, 250, 205),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "River Flow".to_string(),
@@ -1983,6 +2305,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (0, 192, 255),
              permissions: (135, 206, 235),
              row_number: (240, 248, 255),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Storm Cloud".to_string(),
@@ -1998,6 +2321,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (150, 150, 150),
              permissions: (192, 192, 192),
              row_number: (211, 211, 211),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Meadow Grass".to_string(),
@@ -2013,6 +2337,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (73, 255, 73),
              permissions: (76, 228, 144),
              row_number: (152, 251, 152),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Coral Reef".to_string(),
@@ -2028,6 +2353,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (255, 127, 80),
              permissions: (255, 104, 75),
              row_number: (255, 228, 196),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Volcano Lava".to_string(),
@@ -2043,6 +2369,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (255, 0, 0),
              permissions: (255, 104, 75),
              row_number: (255, 182, 193),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Glacier Blue".to_string(),
@@ -2058,6 +2385,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (0, 192, 255),
              permissions: (135, 206, 235),
              row_number: (240, 248, 255),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Rainforest Canopy".to_string(),
@@ -2073,6 +2401,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (0, 255, 0),
              permissions: (73, 255, 73),
              row_number: (144, 238, 144),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Desert Oasis".to_string(),
@@ -2088,6 +2417,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (210, 180, 140),
              permissions: (173, 216, 230),
              row_number: (255, 239, 213),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Northern Lights".to_string(),
@@ -2103,6 +2433,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (0, 255, 0),
              permissions: (186, 85, 211),
              row_number: (221, 160, 221),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Cherry Blossom".to_string(),
@@ -2118,6 +2449,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (255, 182, 193),
              permissions: (255, 105, 180),
              row_number: (255, 228, 225),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Thunderstorm".to_string(),
@@ -2133,6 +2465,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (164, 0, 255),
              permissions: (152, 47, 249),
              row_number: (221, 160, 221),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Wildfire".to_string(),
@@ -2148,6 +2481,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (255, 0, 0),
              permissions: (255, 176, 0),
              row_number: (255, 182, 193),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Crystal Clear".to_string(),
@@ -2163,6 +2497,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (173, 216, 230),
              permissions: (135, 206, 235),
              row_number: (255, 255, 255),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Moonlight".to_string(),
@@ -2178,6 +2513,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (192, 192, 192),
              permissions: (220, 220, 220),
              row_number: (248, 248, 255),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Starry Night".to_string(),
@@ -2193,6 +2529,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (150, 150, 150),
              permissions: (173, 216, 230),
              row_number: (54, 54, 255),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Emerald Isle".to_string(),
@@ -2208,6 +2545,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (0, 255, 0),
              permissions: (144, 238, 144),
              row_number: (173, 255, 47),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Ruby Red".to_string(),
@@ -2223,6 +2561,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (255, 30, 90),
              permissions: (237, 106, 106),
              row_number: (255, 182, 193),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Sapphire Sea".to_string(),
@@ -2238,6 +2577,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (0, 0, 255),
              permissions: (82, 153, 213),
              row_number: (176, 224, 230),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Golden Hour".to_string(),
@@ -2253,6 +2593,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (255, 215, 0),
              permissions: (255, 159, 0),
              row_number: (255, 250, 205),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Silver Moon".to_string(),
@@ -2268,6 +2609,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (192, 192, 192),
              permissions: (150, 150, 150),
              row_number: (248, 248, 255),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Bronze Age".to_string(),
@@ -2283,6 +2625,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (241, 149, 58),
              permissions: (222, 184, 135),
              row_number: (255, 239, 213),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Platinum".to_string(),
@@ -2298,6 +2641,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (229, 228, 226),
              permissions: (211, 211, 211),
              row_number: (248, 248, 255),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Copper Rust".to_string(),
@@ -2313,6 +2657,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (236, 147, 65),
              permissions: (244, 164, 96),
              row_number: (255, 218, 185),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Iron Gray".to_string(),
@@ -2328,6 +2673,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (150, 150, 150),
              permissions: (192, 192, 192),
              row_number: (211, 211, 211),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Steel Blue".to_string(),
@@ -2343,6 +2689,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (82, 153, 213),
              permissions: (173, 216, 230),
              row_number: (240, 248, 255),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Neon Glow".to_string(),
@@ -2358,6 +2705,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (255, 0, 255),
              permissions: (0, 255, 0),
              row_number: (255, 21, 156),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Pastel Dream".to_string(),
@@ -2373,6 +2721,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (255, 182, 193),
              permissions: (221, 160, 221),
              row_number: (255, 228, 225),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Vibrant Vibes".to_string(),
@@ -2388,6 +2737,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (255, 0, 0),
              permissions: (0, 255, 0),
              row_number: (255, 105, 180),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Monochrome".to_string(),
@@ -2403,6 +2753,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (150, 150, 150),
              permissions: (150, 150, 150),
              row_number: (211, 211, 211),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Rainbow".to_string(),
@@ -2418,6 +2769,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (255, 0, 0),
              permissions: (0, 255, 0),
              row_number: (255, 21, 156),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Earth Tones".to_string(),
@@ -2433,6 +2785,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (255, 136, 37),
              permissions: (222, 184, 135),
              row_number: (255, 239, 213),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Sky Blue".to_string(),
@@ -2448,6 +2801,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (135, 206, 235),
              permissions: (176, 224, 230),
              row_number: (255, 255, 255),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Fire Orange".to_string(),
@@ -2463,6 +2817,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (255, 95, 0),
              permissions: (255, 104, 75),
              row_number: (255, 218, 185),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Ice White".to_string(),
@@ -2478,6 +2833,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (255, 255, 255),
              permissions: (173, 216, 230),
              row_number: (248, 248, 255),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Shadow Black".to_string(),
@@ -2493,6 +2849,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (150, 150, 150),
              permissions: (150, 150, 150),
              row_number: (150, 150, 150),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Light Gray".to_string(),
@@ -2508,6 +2865,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (211, 211, 211),
              permissions: (169, 169, 169),
              row_number: (245, 245, 245),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Dark Mode".to_string(),
@@ -2523,6 +2881,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (150, 150, 150),
              permissions: (150, 150, 150),
              row_number: (150, 150, 150),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "High Contrast".to_string(),
@@ -2538,6 +2897,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (150, 150, 150),
              permissions: (255, 255, 255),
              row_number: (255, 255, 255),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Soft Pastels".to_string(),
@@ -2553,6 +2913,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (255, 182, 193),
              permissions: (144, 238, 144),
              row_number: (255, 228, 225),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Bold Colors".to_string(),
@@ -2568,6 +2929,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (255, 0, 0),
              permissions: (0, 255, 0),
              row_number: (255, 105, 180),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Ocean Breeze".to_string(),
@@ -2583,6 +2945,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (100, 149, 237),
              permissions: (31, 151, 255),
              row_number: (74, 119, 255),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Forest Whisper".to_string(),
@@ -2598,6 +2961,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (0, 255, 0),
              permissions: (73, 255, 73),
              row_number: (76, 228, 144),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Desert Sunset".to_string(),
@@ -2613,6 +2977,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (255, 104, 75),
              permissions: (255, 159, 0),
              row_number: (255, 228, 181),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Mountain Peak".to_string(),
@@ -2628,6 +2993,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (150, 150, 150),
              permissions: (150, 150, 150),
              row_number: (169, 169, 169),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Autumn Leaves".to_string(),
@@ -2643,6 +3009,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (255, 127, 80),
              permissions: (255, 95, 0),
              row_number: (255, 228, 181),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Polar Ice".to_string(),
@@ -2658,6 +3025,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (31, 151, 255),
              permissions: (0, 0, 255),
              row_number: (0, 0, 255),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Tropical Storm".to_string(),
@@ -2673,6 +3041,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (173, 216, 230),
              permissions: (176, 196, 222),
              row_number: (240, 248, 255),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Lavender Field".to_string(),
@@ -2688,6 +3057,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (255, 0, 255),
              permissions: (218, 112, 214),
              row_number: (255, 182, 193),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Golden Sands".to_string(),
@@ -2703,6 +3073,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (237, 106, 106),
              permissions: (255, 127, 80),
              row_number: (255, 160, 122),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Emerald Grove".to_string(),
@@ -2718,6 +3089,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (76, 228, 144),
              permissions: (0, 255, 0),
              row_number: (144, 238, 144),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Crimson Tide".to_string(),
@@ -2733,6 +3105,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (255, 95, 0),
              permissions: (255, 159, 0),
              row_number: (255, 176, 0),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Azure Sky".to_string(),
@@ -2748,6 +3121,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (173, 216, 230),
              permissions: (176, 196, 222),
              row_number: (240, 248, 255),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Volcanic Ash".to_string(),
@@ -2763,6 +3137,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (131, 150, 168),
              permissions: (131, 150, 168),
              row_number: (176, 196, 222),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Cherry Blossom".to_string(),
@@ -2778,6 +3153,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (255, 0, 0),
              permissions: (255, 30, 90),
              row_number: (255, 0, 0),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Midnight Blue".to_string(),
@@ -2793,6 +3169,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (176, 196, 222),
              permissions: (240, 248, 255),
              row_number: (255, 255, 255),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Sunflower Field".to_string(),
@@ -2808,6 +3185,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (255, 0, 0),
              permissions: (255, 30, 90),
              row_number: (255, 0, 0),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Coral Reef".to_string(),
@@ -2823,6 +3201,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (255, 0, 0),
              permissions: (255, 21, 156),
              row_number: (255, 105, 180),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Pine Forest".to_string(),
@@ -2838,6 +3217,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (76, 228, 144),
              permissions: (0, 255, 0),
              row_number: (144, 238, 144),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Rose Garden".to_string(),
@@ -2853,6 +3233,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (237, 106, 106),
              permissions: (255, 57, 88),
              row_number: (255, 0, 0),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Snowy Peak".to_string(),
@@ -2868,6 +3249,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (0, 192, 255),
              permissions: (0, 0, 255),
              row_number: (0, 0, 255),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Amber Glow".to_string(),
@@ -2883,6 +3265,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (255, 0, 0),
              permissions: (255, 30, 90),
              row_number: (255, 0, 0),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Meadow Green".to_string(),
@@ -2898,6 +3281,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (169, 225, 55),
              permissions: (173, 255, 47),
              row_number: (0, 255, 0),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Ruby Red".to_string(),
@@ -2913,6 +3297,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (255, 95, 0),
              permissions: (255, 159, 0),
              row_number: (255, 176, 0),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Sapphire Sea".to_string(),
@@ -2928,6 +3313,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (240, 248, 255),
              permissions: (255, 255, 255),
              row_number: (192, 192, 192),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Twilight Purple".to_string(),
@@ -2943,6 +3329,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (255, 105, 180),
              permissions: (255, 21, 156),
              row_number: (255, 0, 150),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Ivory Coast".to_string(),
@@ -2958,6 +3345,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (255, 104, 75),
              permissions: (255, 95, 0),
              row_number: (237, 106, 106),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Jade Garden".to_string(),
@@ -2973,6 +3361,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (76, 228, 144),
              permissions: (0, 255, 0),
              row_number: (144, 238, 144),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Obsidian Night".to_string(),
@@ -2988,6 +3377,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (255, 255, 255),
              permissions: (240, 248, 255),
              row_number: (176, 196, 222),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Pearl White".to_string(),
@@ -3003,6 +3393,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (150, 150, 150),
              permissions: (150, 150, 150),
              row_number: (150, 150, 150),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Cinnamon Spice".to_string(),
@@ -3018,6 +3409,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (255, 176, 0),
              permissions: (255, 215, 0),
              row_number: (255, 255, 0),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Mint Breeze".to_string(),
@@ -3033,6 +3425,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (169, 225, 55),
              permissions: (173, 255, 47),
              row_number: (0, 255, 0),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Garnet Flame".to_string(),
@@ -3048,6 +3441,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (255, 95, 0),
              permissions: (255, 159, 0),
              row_number: (255, 176, 0),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Indigo Dream".to_string(),
@@ -3063,6 +3457,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (255, 182, 193),
              permissions: (255, 105, 180),
              row_number: (255, 21, 156),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Onyx Shadow".to_string(),
@@ -3078,6 +3473,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (220, 220, 220),
              permissions: (245, 245, 245),
              row_number: (255, 255, 255),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Topaz Sun".to_string(),
@@ -3093,6 +3489,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (255, 0, 0),
              permissions: (255, 30, 90),
              row_number: (255, 0, 0),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Emerald Isle".to_string(),
@@ -3108,6 +3505,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (76, 228, 144),
              permissions: (0, 255, 0),
              row_number: (144, 238, 144),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Amethyst Aura".to_string(),
@@ -3123,6 +3521,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (255, 0, 150),
              permissions: (255, 30, 90),
              row_number: (255, 0, 0),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Copper Rust".to_string(),
@@ -3138,6 +3537,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (255, 127, 80),
              permissions: (255, 160, 122),
              row_number: (255, 95, 0),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Silver Moon".to_string(),
@@ -3153,6 +3553,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (135, 206, 250),
              permissions: (100, 149, 237),
              row_number: (74, 119, 255),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Bronze Age".to_string(),
@@ -3168,6 +3569,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (255, 104, 75),
              permissions: (255, 127, 80),
              row_number: (255, 160, 122),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Platinum Frost".to_string(),
@@ -3183,6 +3585,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (150, 150, 150),
              permissions: (150, 150, 150),
              row_number: (150, 150, 150),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Turquoise Lagoon".to_string(),
@@ -3198,6 +3601,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (135, 206, 250),
              permissions: (173, 216, 230),
              row_number: (176, 196, 222),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Gold Horizon".to_string(),
@@ -3213,6 +3617,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (255, 0, 0),
              permissions: (255, 30, 90),
              row_number: (255, 0, 0),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Crystal Clear".to_string(),
@@ -3228,6 +3633,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (0, 192, 255),
              permissions: (0, 0, 255),
              row_number: (0, 0, 255),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Ebony Wood".to_string(),
@@ -3243,6 +3649,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (220, 220, 220),
              permissions: (245, 245, 245),
              row_number: (255, 255, 255),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Scarlet Flame".to_string(),
@@ -3258,6 +3665,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (255, 160, 122),
              permissions: (255, 95, 0),
              row_number: (255, 159, 0),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Azure Wave".to_string(),
@@ -3273,6 +3681,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (240, 248, 255),
              permissions: (255, 255, 255),
              row_number: (192, 192, 192),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Verdant Valley".to_string(),
@@ -3288,6 +3697,7 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (76, 228, 144),
              permissions: (0, 255, 0),
              row_number: (144, 238, 144),
+             extra: ExtraColors::default(),
          },
          Theme {
              name: "Cobalt Storm".to_string(),
@@ -3303,10 +3713,34 @@ pub fn get_themes() -> Vec<Theme> {
              modified: (240, 248, 255),
              permissions: (255, 255, 255),
              row_number: (192, 192, 192),
+             extra: ExtraColors::default(),
          },
       ]
 }
 
+/// Built-in themes followed by the user themes from `~/.config/lsa/themes`.
+/// A user theme with the name of a built-in one replaces it. The theme files
+/// are read once per run.
+pub fn get_themes() -> &'static [Theme] {
+    static THEMES: OnceLock<Vec<Theme>> = OnceLock::new();
+
+    THEMES.get_or_init(|| {
+        let mut themes = builtin_themes();
+        let (user_themes, errors) = load_user_themes(&themes);
+        for error in &errors {
+            eprintln!("Warning: Skipping theme {error}");
+        }
+
+        for theme in user_themes {
+            match themes.iter_mut().find(|t| t.name == theme.name) {
+                Some(existing) => *existing = theme,
+                None => themes.push(theme),
+            }
+        }
+        themes
+    })
+}
+
 pub fn get_theme_by_name(name: &str) -> Option<Theme> {
-    get_themes().into_iter().find(|t| t.name == name)
+    get_themes().iter().find(|t| t.name == name).cloned()
 }
\ No newline at end of file
@@ -0,0 +1,340 @@
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::de::{self, Deserializer, SeqAccess, Visitor};
+use serde::Deserialize;
+
+use crate::themes::{ExtraColors, Theme};
+
+pub fn themes_dir() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("lsa")
+        .join("themes")
+}
+
+/// A color slot in a theme file, written either as `"#rrggbb"` or `[r, g, b]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Rgb((u8, u8, u8));
+
+impl<'de> Deserialize<'de> for Rgb {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct RgbVisitor;
+
+        impl<'de> Visitor<'de> for RgbVisitor {
+            type Value = Rgb;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a color as \"#rrggbb\" or [r, g, b]")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Rgb, E> {
+                parse_hex(value).map(Rgb).ok_or_else(|| {
+                    E::custom(format!(
+                        "invalid color \"{value}\", expected \"#rrggbb\" or [r, g, b]"
+                    ))
+                })
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Rgb, A::Error> {
+                let mut channels = [0u8; 3];
+                for (i, channel) in channels.iter_mut().enumerate() {
+                    let value: i64 = seq
+                        .next_element()?
+                        .ok_or_else(|| de::Error::invalid_length(i, &self))?;
+                    *channel = u8::try_from(value).map_err(|_| {
+                        de::Error::custom(format!(
+                            "color channel {value} is out of range, expected 0-255"
+                        ))
+                    })?;
+                }
+                if seq.next_element::<i64>()?.is_some() {
+                    return Err(de::Error::invalid_length(4, &self));
+                }
+                Ok(Rgb((channels[0], channels[1], channels[2])))
+            }
+        }
+
+        deserializer.deserialize_any(RgbVisitor)
+    }
+}
+
+pub fn parse_hex(value: &str) -> Option<(u8, u8, u8)> {
+    let hex = value.trim().trim_start_matches('#');
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some((channel(0)?, channel(2)?, channel(4)?))
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ThemeFile {
+    name: Option<String>,
+    description: Option<String>,
+    inherits: Option<String>,
+    border: Option<Rgb>,
+    header: Option<Rgb>,
+    file_name: Option<Rgb>,
+    file_type: Option<Rgb>,
+    dir_name: Option<Rgb>,
+    dir_type: Option<Rgb>,
+    file_size: Option<Rgb>,
+    dir_size: Option<Rgb>,
+    modified: Option<Rgb>,
+    permissions: Option<Rgb>,
+    row_number: Option<Rgb>,
+    symlink: Option<Rgb>,
+    broken_link: Option<Rgb>,
+    owner: Option<Rgb>,
+    group: Option<Rgb>,
+    inode: Option<Rgb>,
+    links: Option<Rgb>,
+    atime: Option<Rgb>,
+    ctime: Option<Rgb>,
+    btime: Option<Rgb>,
+    octal: Option<Rgb>,
+    git_modified: Option<Rgb>,
+    git_staged: Option<Rgb>,
+    git_untracked: Option<Rgb>,
+    git_ignored: Option<Rgb>,
+    git_conflicted: Option<Rgb>,
+}
+
+impl ThemeFile {
+    fn into_theme(self, default_name: &str, builtin: &[Theme]) -> Result<Theme, String> {
+        let base = match &self.inherits {
+            Some(parent) => Some(
+                builtin
+                    .iter()
+                    .find(|t| &t.name == parent)
+                    .ok_or_else(|| format!("unknown theme \"{parent}\" in `inherits`"))?,
+            ),
+            None => None,
+        };
+
+        let mut missing = Vec::new();
+        let mut slot = |value: Option<Rgb>, field: &'static str, inherited: Option<(u8, u8, u8)>| {
+            match value.map(|rgb| rgb.0).or(inherited) {
+                Some(color) => color,
+                None => {
+                    missing.push(format!("`{field}`"));
+                    (0, 0, 0)
+                }
+            }
+        };
+
+        let optional = |value: Option<Rgb>, inherited: Option<(u8, u8, u8)>| {
+            value.map(|rgb| rgb.0).or(inherited)
+        };
+
+        let theme = Theme {
+            name: self.name.clone().unwrap_or_else(|| default_name.to_string()),
+            description: self
+                .description
+                .clone()
+                .or_else(|| base.map(|b| format!("Based on {}", b.name)))
+                .unwrap_or_else(|| "User theme".to_string()),
+            border: slot(self.border, "border", base.map(|b| b.border)),
+            header: slot(self.header, "header", base.map(|b| b.header)),
+            file_name: slot(self.file_name, "file_name", base.map(|b| b.file_name)),
+            file_type: slot(self.file_type, "file_type", base.map(|b| b.file_type)),
+            dir_name: slot(self.dir_name, "dir_name", base.map(|b| b.dir_name)),
+            dir_type: slot(self.dir_type, "dir_type", base.map(|b| b.dir_type)),
+            file_size: slot(self.file_size, "file_size", base.map(|b| b.file_size)),
+            dir_size: slot(self.dir_size, "dir_size", base.map(|b| b.dir_size)),
+            modified: slot(self.modified, "modified", base.map(|b| b.modified)),
+            permissions: slot(self.permissions, "permissions", base.map(|b| b.permissions)),
+            row_number: slot(self.row_number, "row_number", base.map(|b| b.row_number)),
+            // Optional, so nothing is reported missing
+            extra: ExtraColors {
+                symlink: optional(self.symlink, base.and_then(|b| b.extra.symlink)),
+                broken_link: optional(self.broken_link, base.and_then(|b| b.extra.broken_link)),
+                owner: optional(self.owner, base.and_then(|b| b.extra.owner)),
+                group: optional(self.group, base.and_then(|b| b.extra.group)),
+                inode: optional(self.inode, base.and_then(|b| b.extra.inode)),
+                links: optional(self.links, base.and_then(|b| b.extra.links)),
+                atime: optional(self.atime, base.and_then(|b| b.extra.atime)),
+                ctime: optional(self.ctime, base.and_then(|b| b.extra.ctime)),
+                btime: optional(self.btime, base.and_then(|b| b.extra.btime)),
+                octal: optional(self.octal, base.and_then(|b| b.extra.octal)),
+                git_modified: optional(self.git_modified, base.and_then(|b| b.extra.git_modified)),
+                git_staged: optional(self.git_staged, base.and_then(|b| b.extra.git_staged)),
+                git_untracked: optional(self.git_untracked, base.and_then(|b| b.extra.git_untracked)),
+                git_ignored: optional(self.git_ignored, base.and_then(|b| b.extra.git_ignored)),
+                git_conflicted: optional(self.git_conflicted, base.and_then(|b| b.extra.git_conflicted)),
+            },
+        };
+
+        if !missing.is_empty() {
+            return Err(format!(
+                "missing color {} (set it or use `inherits` with a built-in theme)",
+                missing.join(", ")
+            ));
+        }
+        Ok(theme)
+    }
+}
+
+/// Parses a theme file. The format is picked from the extension and a
+/// theme without `name` is named after the file.
+pub fn parse_theme_file(path: &Path, builtin: &[Theme]) -> Result<Theme, String> {
+    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+
+    let file: ThemeFile = match extension.as_str() {
+        "toml" => toml::from_str(&content).map_err(|e| e.to_string())?,
+        "yaml" | "yml" => serde_yaml::from_str(&content).map_err(|e| e.to_string())?,
+        _ => return Err(format!("unsupported theme format: {extension}")),
+    };
+
+    let default_name = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    file.into_theme(&default_name, builtin)
+}
+
+/// Loads every `*.toml`/`*.yaml` theme in the themes directory. Broken files
+/// are reported with their path and skipped.
+pub fn load_user_themes(builtin: &[Theme]) -> (Vec<Theme>, Vec<String>) {
+    let mut themes = Vec::new();
+    let mut errors = Vec::new();
+
+    let Ok(entries) = fs::read_dir(themes_dir()) else {
+        return (themes, errors);
+    };
+
+    let mut paths: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            matches!(
+                path.extension().and_then(|ext| ext.to_str()),
+                Some("toml" | "yaml" | "yml")
+            )
+        })
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        match parse_theme_file(&path, builtin) {
+            Ok(theme) => themes.push(theme),
+            Err(e) => errors.push(format!("{}: {}", path.display(), e)),
+        }
+    }
+
+    (themes, errors)
+}
+
+/// Serialises a theme in the format read by `parse_theme_file`.
+pub fn theme_to_toml(theme: &Theme) -> String {
+    let quote = |s: &str| toml::Value::String(s.to_string()).to_string();
+    let hex = |c: (u8, u8, u8)| format!("\"#{:02x}{:02x}{:02x}\"", c.0, c.1, c.2);
+
+    let mut lines = vec![
+        format!("name = {}", quote(&theme.name)),
+        format!("description = {}", quote(&theme.description)),
+        String::new(),
+    ];
+    for (field, color) in theme.slots() {
+        lines.push(format!("{field} = {}", hex(color)));
+    }
+    for (field, color) in theme.extra.slots() {
+        if let Some(color) = color {
+            lines.push(format!("{field} = {}", hex(color)));
+        }
+    }
+    lines.join("\n") + "\n"
+}
+
+/// Writes `theme` to `<themes dir>/<name>.toml`. An existing file is only
+/// replaced with `overwrite`.
+pub fn save_user_theme(theme: &Theme, overwrite: bool) -> Result<PathBuf, String> {
+    let dir = themes_dir();
+    fs::create_dir_all(&dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+
+    let path = dir.join(format!("{}.toml", theme.name));
+    if path.exists() && !overwrite {
+        return Err(format!(
+            "{} already exists (use --force to replace it)",
+            path.display()
+        ));
+    }
+    fs::write(&path, theme_to_toml(theme)).map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ThemeFile, parse_hex, theme_to_toml};
+    use crate::themes::builtin_themes;
+
+    #[test]
+    fn test_parse_hex() {
+        assert_eq!(parse_hex("#ff8000"), Some((255, 128, 0)));
+        assert_eq!(parse_hex("FF8000"), Some((255, 128, 0)));
+        assert_eq!(parse_hex("#ff80"), None);
+        assert_eq!(parse_hex("#gg8000"), None);
+    }
+
+    #[test]
+    fn test_inherits_builtin_and_overrides_slots() {
+        let file: ThemeFile = toml::from_str(
+            "inherits = \"nord\"\nborder = \"#010203\"\nheader = [4, 5, 6]\nsymlink = \"#0a0b0c\"\nowner = \"#0d0e0f\"\ngit_staged = \"#102030\"\n",
+        )
+        .unwrap();
+        let theme = file.into_theme("mine", &builtin_themes()).unwrap();
+        let nord = builtin_themes().into_iter().find(|t| t.name == "nord").unwrap();
+
+        assert_eq!(theme.name, "mine");
+        assert_eq!(theme.border, (1, 2, 3));
+        assert_eq!(theme.header, (4, 5, 6));
+        assert_eq!(theme.file_name, nord.file_name);
+        assert_eq!(theme.symlink_color(), (10, 11, 12));
+        assert_eq!(theme.extra.broken_link, None);
+        assert_eq!(theme.owner_color(), (13, 14, 15));
+        // Column slots the file leaves out fall back to a standard slot
+        assert_eq!(theme.group_color(), nord.dir_type);
+        assert_eq!(theme.git_staged_color(), (16, 32, 48));
+        assert_eq!(theme.git_modified_color(), nord.git_modified_color());
+    }
+
+    #[test]
+    fn test_invalid_field_is_named_in_error() {
+        let err = toml::from_str::<ThemeFile>("inherits = \"nord\"\nborder = \"#zz0000\"\n")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("border"), "{err}");
+        assert!(err.contains("#zz0000"), "{err}");
+
+        let err = toml::from_str::<ThemeFile>("boder = \"#000000\"\n")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("unknown field `boder`"), "{err}");
+    }
+
+    #[test]
+    fn test_saved_theme_round_trips() {
+        let nord = builtin_themes().into_iter().find(|t| t.name == "nord").unwrap();
+        let file: ThemeFile = toml::from_str(&theme_to_toml(&nord)).unwrap();
+        let theme = file.into_theme("other", &[]).unwrap();
+
+        assert_eq!(theme.name, "nord");
+        assert_eq!(theme.description, nord.description);
+        assert_eq!(theme.permissions, nord.permissions);
+    }
+
+    #[test]
+    fn test_missing_slots_without_inherits() {
+        let file: ThemeFile = serde_yaml::from_str("border: \"#000000\"\n").unwrap();
+        let err = file.into_theme("mine", &builtin_themes()).unwrap_err();
+        assert!(err.contains("`header`"), "{err}");
+        assert!(!err.contains("`border`"), "{err}");
+    }
+}
@@ -41,6 +41,15 @@ pub fn format_time(time: SystemTime) -> String {
     }
 }
 
+pub fn format_rfc3339(time: SystemTime) -> String {
+    chrono::DateTime::<chrono::Local>::from(time).to_rfc3339()
+}
+
+/// Permission bits as four octal digits, e.g. `0644`.
+pub fn format_octal(mode: u32) -> String {
+    format!("{:04o}", mode & 0o7777)
+}
+
 #[cfg(unix)]
 pub fn format_permissions(mode: u32) -> String {
     let user = format!(
@@ -71,11 +80,12 @@ pub fn format_permissions(_metadata: &std::fs::Metadata) -> String {
 
 
 pub fn colorize_borders(table_str: &str, theme: &crate::themes::Theme) -> String {
-    let border_color = format!(
-        "\x1b[38;2;{};{};{}m",
-        theme.border.0, theme.border.1, theme.border.2
-    );
-    let reset_color = "\x1b[0m";
+    if !crate::color::enabled() {
+        return table_str.to_string();
+    }
+
+    let border_color = crate::color::fg(theme.border);
+    let reset_color = crate::color::reset();
 
     table_str
         .lines()
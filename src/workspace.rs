@@ -1,12 +1,13 @@
 use std::env;
 use std::fs;
-use std::io;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use copypasta::{ClipboardContext, ClipboardProvider};
 use ignore::WalkBuilder;
 
-pub fn collect_files(base: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+use crate::error::{LsaError, report_minor_problem};
+
+pub fn collect_files(base: &Path, files: &mut Vec<PathBuf>) {
     let walker = WalkBuilder::new(base)
         .hidden(false)
         .git_ignore(true)
@@ -25,24 +26,23 @@ pub fn collect_files(base: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
                 }
 
 
-                if path.is_file() {
-                    if let Ok(relative_path) = path.strip_prefix(base) {
-                        files.push(relative_path.to_path_buf());
-                    }
+                if path.is_file()
+                    && let Ok(relative_path) = path.strip_prefix(base)
+                {
+                    files.push(relative_path.to_path_buf());
                 }
             }
             Err(err) => {
                 eprintln!("Warning: Error walking directory: {}", err);
+                report_minor_problem();
             }
         }
     }
-
-    Ok(())
 }
 
 fn is_source_file(path: &Path) -> bool {
     if let Some(extension) = path.extension().and_then(|s| s.to_str()) {
-        match extension.to_lowercase().as_str() {
+        matches!(extension.to_lowercase().as_str(),
 
             "rs" | "py" | "js" | "ts" | "jsx" | "tsx" | "go" | "java" | "c" | "h" | "cpp" | "cc" | "cxx" | "hpp" |
             "cs" | "php" | "rb" | "swift" | "kt" | "dart" | "lua" | "r" | "scala" | "clj" | "cljs" | "hs" |
@@ -52,9 +52,8 @@ fn is_source_file(path: &Path) -> bool {
 
             "json" | "toml" | "yaml" | "yml" | "xml" | "ini" | "conf" | "config" | "env" |
 
-            "md" | "markdown" | "txt" | "rst" | "tex" | "rtf" => true,
-            _ => false,
-        }
+            "md" | "markdown" | "txt" | "rst" | "tex" | "rtf"
+        )
     } else {
         let filename = path
             .file_name()
@@ -69,7 +68,7 @@ fn is_source_file(path: &Path) -> bool {
     }
 }
 
-fn copy_to_clipboard(content: &str) -> io::Result<()> {
+fn copy_to_clipboard(content: &str) -> Result<(), LsaError> {
 
     #[cfg(unix)]
     {
@@ -121,37 +120,22 @@ fn copy_to_clipboard(content: &str) -> io::Result<()> {
         }
     }
 
-    match ClipboardContext::new() {
-        Ok(mut ctx) => {
-            match ctx.set_contents(content.to_string()) {
-                Ok(_) => {
-                    println!("\u{f00c} Content copied to clipboard!");
-                    Ok(())
-                }
-                Err(e) => {
-                    eprintln!("\u{f00d} Failed to copy to clipboard: {}", e);
-                    Err(io::Error::new(io::ErrorKind::Other, e))
-                }
-            }
-        }
-        Err(e) => {
-            eprintln!("\u{f00d} Failed to access clipboard: {}", e);
-            Err(io::Error::new(io::ErrorKind::Other, e))
-        }
-    }
+    let mut ctx = ClipboardContext::new().map_err(|e| LsaError::Clipboard(e.to_string()))?;
+    ctx.set_contents(content.to_string())
+        .map_err(|e| LsaError::Clipboard(e.to_string()))?;
+    println!("\u{f00c} Content copied to clipboard!");
+    Ok(())
 }
 
-pub fn copy_file_to_clipboard(file_path: &str) -> io::Result<()> {
+pub fn copy_file_to_clipboard(file_path: &str) -> Result<(), LsaError> {
     let path = Path::new(file_path);
 
-    if !path.exists() {
-        eprintln!("\u{f00d} File not found: {}", file_path);
-        return Err(io::Error::new(io::ErrorKind::NotFound, "File not found"));
-    }
-
-    if !path.is_file() {
-        eprintln!("\u{f00d} Path is not a file: {}", file_path);
-        return Err(io::Error::new(io::ErrorKind::InvalidInput, "Path is not a file"));
+    let metadata = fs::metadata(path).map_err(|e| LsaError::io(path, e))?;
+    if !metadata.is_file() {
+        return Err(LsaError::WrongKind {
+            path: path.to_path_buf(),
+            expected: "file",
+        });
     }
 
     let content = fs::read_to_string(path)
@@ -168,17 +152,15 @@ pub fn copy_file_to_clipboard(file_path: &str) -> io::Result<()> {
     copy_to_clipboard(&workspace_content)
 }
 
-pub fn copy_folder_to_clipboard(folder_path: &str) -> io::Result<()> {
+pub fn copy_folder_to_clipboard(folder_path: &str) -> Result<(), LsaError> {
     let folder = Path::new(folder_path);
 
-    if !folder.exists() {
-        eprintln!("\u{f00d} Folder not found: {}", folder_path);
-        return Err(io::Error::new(io::ErrorKind::NotFound, "Folder not found"));
-    }
-
-    if !folder.is_dir() {
-        eprintln!("\u{f00d} Path is not a folder: {}", folder_path);
-        return Err(io::Error::new(io::ErrorKind::InvalidInput, "Path is not a folder"));
+    let metadata = fs::metadata(folder).map_err(|e| LsaError::io(folder, e))?;
+    if !metadata.is_dir() {
+        return Err(LsaError::WrongKind {
+            path: folder.to_path_buf(),
+            expected: "folder",
+        });
     }
 
     let mut workspace_content = String::new();
@@ -209,6 +191,7 @@ pub fn copy_folder_to_clipboard(folder_path: &str) -> io::Result<()> {
             }
             Err(err) => {
                 eprintln!("Warning: Error walking directory: {}", err);
+                report_minor_problem();
             }
         }
     }
@@ -228,14 +211,14 @@ pub fn copy_folder_to_clipboard(folder_path: &str) -> io::Result<()> {
     copy_to_clipboard(&workspace_content)
 }
 
-pub fn print_workspace_snapshot(source_only: bool, max_size_kb: Option<usize>) -> io::Result<()> {
-    let base = env::current_dir()?;
+pub fn print_workspace_snapshot(source_only: bool, max_size_kb: Option<usize>) -> Result<(), LsaError> {
+    let base = env::current_dir().map_err(LsaError::CurrentDir)?;
     let mut workspace_content = String::new();
 
     workspace_content.push_str(&format!("\u{f07c} Workspace: {}\n\n", base.display()));
 
     let mut files = Vec::new();
-    collect_files(&base, &mut files)?;
+    collect_files(&base, &mut files);
 
 
     if source_only {
@@ -253,11 +236,11 @@ pub fn print_workspace_snapshot(source_only: bool, max_size_kb: Option<usize>) -
         let file_section = format!("./{}\n---------------------\n{}\n\n", relative_path.display(), content);
 
 
-        if let Some(max_bytes) = max_size_bytes {
-            if total_size + file_section.len() > max_bytes {
-                workspace_content.push_str(&format!("... (truncated due to {}KB size limit)\n", max_size_kb.unwrap()));
-                break;
-            }
+        if let Some(max_bytes) = max_size_bytes
+            && total_size + file_section.len() > max_bytes
+        {
+            workspace_content.push_str(&format!("... (truncated due to {}KB size limit)\n", max_size_kb.unwrap()));
+            break;
         }
 
         workspace_content.push_str(&file_section);